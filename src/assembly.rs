@@ -0,0 +1,483 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use glam::{dvec2, DVec2};
+
+use crate::components::rocket::{Engine, FuelTank, Rocket, Stage, DRAG_AREA, DRAG_COEFFICIENT};
+
+//looked up relative to the working directory so designs can change without a rebuild
+pub const ROCKETS_DIR: &str = "rockets";
+pub const CATALOGUE_FILE: &str = "parts.txt";
+pub const ASSEMBLY_EXTENSION: &str = "rocket";
+pub const DEFAULT_ROCKET: &str = "saturn_v";
+
+//masses are dry masses in kg
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Part {
+    Engine {
+        thrust: f64,
+        isp: f64,
+        mass: f64,
+        //unit vector the engine pushes the rocket along, (0,-1) is forward
+        direction: DVec2,
+        offset: DVec2,
+    },
+    Tank {
+        fuel: f64,
+        mass: f64,
+    },
+    CommandPod {
+        mass: f64,
+    },
+    ReactionWheel {
+        torque: f64,
+        mass: f64,
+    },
+    //separates its stage from the one above
+    Decoupler {
+        mass: f64,
+    },
+}
+
+impl Part {
+    pub fn mass(&self) -> f64 {
+        match *self {
+            Part::Engine { mass, .. }
+            | Part::Tank { mass, .. }
+            | Part::CommandPod { mass }
+            | Part::ReactionWheel { mass, .. }
+            | Part::Decoupler { mass } => mass,
+        }
+    }
+}
+
+pub type Catalogue = HashMap<String, Part>;
+
+//`rockets/<name>.rocket` assembled from the parts of `rockets/parts.txt`
+pub fn load_rocket(name: &str) -> Result<Rocket, String> {
+    let dir = Path::new(ROCKETS_DIR);
+    let catalogue_path = dir.join(CATALOGUE_FILE);
+    let catalogue = parse_catalogue(&read(&catalogue_path)?)
+        .map_err(|e| format!("{}: {}", catalogue_path.display(), e))?;
+    let assembly_path = dir.join(format!("{}.{}", name, ASSEMBLY_EXTENSION));
+    parse_assembly(&read(&assembly_path)?, &catalogue)
+        .map_err(|e| format!("{}: {}", assembly_path.display(), e))
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+//one part per line: `<kind> <name> key=value ...`
+pub fn parse_catalogue(text: &str) -> Result<Catalogue, String> {
+    let mut catalogue = Catalogue::new();
+    for (number, words) in lines(text) {
+        let at_line = |e: String| format!("line {}: {}", number, e);
+        let (kind, name) = match words.as_slice() {
+            [kind, name, ..] => (*kind, *name),
+            _ => return Err(at_line("expected a part kind and a name".to_string())),
+        };
+        let mut properties = Properties::parse(&words[2..]).map_err(at_line)?;
+        let part = match kind {
+            "engine" => Part::Engine {
+                thrust: properties.number("thrust"),
+                isp: properties.number("isp"),
+                mass: properties.number("mass"),
+                direction: properties.vector("direction").normalize_or_zero(),
+                offset: properties.vector("offset"),
+            },
+            "tank" => Part::Tank {
+                fuel: properties.number("fuel"),
+                mass: properties.number("mass"),
+            },
+            "command_pod" => Part::CommandPod {
+                mass: properties.number("mass"),
+            },
+            "reaction_wheel" => Part::ReactionWheel {
+                torque: properties.number("torque"),
+                mass: properties.number("mass"),
+            },
+            "decoupler" => Part::Decoupler {
+                mass: properties.number("mass"),
+            },
+            other => return Err(at_line(format!("unknown part kind '{}'", other))),
+        };
+        properties.finish().map_err(at_line)?;
+        if let Part::Engine { direction, isp, .. } = part {
+            if direction == DVec2::ZERO || isp <= 0.0 {
+                return Err(at_line(format!(
+                    "engine '{}' needs a direction and a positive isp",
+                    name
+                )));
+            }
+        }
+        if catalogue.insert(name.to_string(), part).is_some() {
+            return Err(at_line(format!("part '{}' is defined twice", name)));
+        }
+    }
+    Ok(catalogue)
+}
+
+//optional `drag coefficient=<c> area=<m2>` and `stage length=<m> width=<m>
+//<part>[*count] ...` lines, stages from the top, every stage but the top one
+//needs a decoupler, the hull is the stages stacked
+pub fn parse_assembly(text: &str, catalogue: &Catalogue) -> Result<Rocket, String> {
+    let mut rocket = Rocket {
+        stages: Vec::new(),
+        drag_coefficient: DRAG_COEFFICIENT,
+        drag_area: DRAG_AREA,
+        length: 0.0,
+        width: 0.0,
+    };
+    for (number, words) in lines(text) {
+        let at_line = |e: String| format!("line {}: {}", number, e);
+        match words[0] {
+            "drag" => {
+                let mut properties = Properties::parse(&words[1..]).map_err(at_line)?;
+                rocket.drag_coefficient = properties.number("coefficient");
+                rocket.drag_area = properties.number("area");
+                properties.finish().map_err(at_line)?;
+            }
+            "stage" => {
+                let (sizes, names): (Vec<&str>, Vec<&str>) =
+                    words[1..].iter().partition(|w| w.contains('='));
+                let mut properties = Properties::parse(&sizes).map_err(at_line)?;
+                let length = properties.number("length");
+                let width = properties.number("width");
+                properties.finish().map_err(at_line)?;
+                if length <= 0.0 || width <= 0.0 {
+                    return Err(at_line(
+                        "stage needs a positive length and width".to_string(),
+                    ));
+                }
+                let mut parts = Vec::new();
+                for word in &names {
+                    let (name, count) = match word.split_once('*') {
+                        Some((name, count)) => (
+                            name,
+                            count
+                                .parse()
+                                .map_err(|_| at_line(format!("invalid count in '{}'", word)))?,
+                        ),
+                        None => (*word, 1),
+                    };
+                    let part = catalogue
+                        .get(name)
+                        .ok_or_else(|| at_line(format!("unknown part '{}'", name)))?;
+                    parts.extend(std::iter::repeat(*part).take(count));
+                }
+                let decoupled = parts.iter().any(|p| matches!(p, Part::Decoupler { .. }));
+                if !rocket.stages.is_empty() && !decoupled {
+                    return Err(at_line("stage has no decoupler".to_string()));
+                }
+                rocket.length += length;
+                rocket.width = rocket.width.max(width);
+                rocket.stages.push(Stage {
+                    length,
+                    width,
+                    ..assemble_stage(&parts)
+                });
+            }
+            other => return Err(at_line(format!("unknown entry '{}'", other))),
+        }
+    }
+    if rocket.stages.is_empty() {
+        return Err("assembly has no stages".to_string());
+    }
+    Ok(rocket)
+}
+
+//engines are grouped by the direction they push in, each group acts as one engine
+fn assemble_stage(parts: &[Part]) -> Stage {
+    let mut groups = [(DVec2::ZERO, 0.0, DVec2::ZERO, 0.0); 4];
+    let mut fuel = 0.0;
+    let mut reaction_wheel_torque = 0.0;
+    for part in parts {
+        match *part {
+            Part::Engine {
+                thrust,
+                isp,
+                direction,
+                offset,
+                ..
+            } => {
+                let slot = if direction.y.abs() >= direction.x.abs() {
+                    if direction.y < 0.0 {
+                        0
+                    } else {
+                        1
+                    }
+                } else if direction.x > 0.0 {
+                    2
+                } else {
+                    3
+                };
+                let (vector, mass_flow, moment, total) = &mut groups[slot];
+                *vector += direction * thrust;
+                *mass_flow += thrust / isp;
+                *moment += offset * thrust;
+                *total += thrust;
+            }
+            Part::Tank { fuel: f, .. } => fuel += f,
+            Part::ReactionWheel { torque, .. } => reaction_wheel_torque += torque,
+            _ => {}
+        }
+    }
+    let [averse, reverse, left, right] = groups.map(|(vector, mass_flow, moment, total)| {
+        if total == 0.0 {
+            return Engine {
+                vector: DVec2::ZERO,
+                throttle: 0.0,
+                isp: 1.0,
+                offset: DVec2::ZERO,
+            };
+        }
+        Engine {
+            vector,
+            throttle: 0.0,
+            isp: total / mass_flow,
+            offset: moment / total,
+        }
+    });
+    Stage {
+        engine_averse: averse,
+        engine_reverse: reverse,
+        engine_left: left,
+        engine_right: right,
+        dry_mass: parts.iter().map(|p| p.mass()).sum(),
+        tank: FuelTank::full(fuel),
+        reaction_wheel_torque,
+        length: 0.0,
+        width: 0.0,
+    }
+}
+
+//numbered non-empty lines split into words, `#` starts a comment
+fn lines(text: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    text.lines().enumerate().filter_map(|(i, line)| {
+        let line = line.split('#').next().unwrap_or("");
+        let words: Vec<_> = line.split_whitespace().collect();
+        if words.is_empty() {
+            None
+        } else {
+            Some((i + 1, words))
+        }
+    })
+}
+
+//`key=value` words, every key has to be used exactly once
+struct Properties<'a> {
+    values: HashMap<&'a str, &'a str>,
+    errors: Vec<String>,
+}
+
+impl<'a> Properties<'a> {
+    fn parse(words: &[&'a str]) -> Result<Self, String> {
+        let mut values = HashMap::new();
+        for word in words {
+            let (key, value) = word
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, found '{}'", word))?;
+            if values.insert(key, value).is_some() {
+                return Err(format!("'{}' is given twice", key));
+            }
+        }
+        Ok(Properties {
+            values,
+            errors: Vec::new(),
+        })
+    }
+
+    fn take(&mut self, key: &str) -> Option<&'a str> {
+        let value = self.values.remove(key);
+        if value.is_none() {
+            self.errors.push(format!("missing '{}'", key));
+        }
+        value
+    }
+
+    fn number(&mut self, key: &str) -> f64 {
+        let value = match self.take(key) {
+            Some(value) => value,
+            None => return 0.0,
+        };
+        match value.parse::<f64>() {
+            Ok(n) if n.is_finite() && n >= 0.0 => n,
+            _ => {
+                self.errors.push(format!(
+                    "'{}' must be a non-negative number, found '{}'",
+                    key, value
+                ));
+                0.0
+            }
+        }
+    }
+
+    fn vector(&mut self, key: &str) -> DVec2 {
+        let value = match self.take(key) {
+            Some(value) => value,
+            None => return DVec2::ZERO,
+        };
+        let parsed = value
+            .split_once(',')
+            .and_then(|(x, y)| Some(dvec2(x.parse().ok()?, y.parse().ok()?)));
+        match parsed {
+            Some(v) if v.is_finite() => v,
+            _ => {
+                self.errors
+                    .push(format!("'{}' must be <x>,<y>, found '{}'", key, value));
+                DVec2::ZERO
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        let mut errors = self.errors;
+        let mut unknown: Vec<_> = self.values.keys().collect();
+        unknown.sort();
+        errors.extend(
+            unknown
+                .iter()
+                .map(|key| format!("unknown property '{}'", key)),
+        );
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::celestial_body::CelestialBodyType, gravity::G, system_generation::get_system,
+        SPACE_SIZE,
+    };
+
+    const CATALOGUE: &str = "
+        command_pod pod mass=1000 #crew
+        reaction_wheel wheel torque=300 mass=20
+        decoupler ring mass=100
+        tank small fuel=500 mass=50
+        engine main thrust=2000 isp=300 mass=200 direction=0,-1 offset=0,2
+        engine side thrust=100 isp=200 mass=10 direction=1,0 offset=0,-1
+    ";
+
+    #[test]
+    fn assembles_stages_from_the_top() {
+        let catalogue = parse_catalogue(CATALOGUE).unwrap();
+        let rocket = parse_assembly(
+            "drag coefficient=0.5 area=4\n\
+             stage length=4 width=1 pod wheel small main\n\
+             stage length=6 width=2 ring small*2 main*2 side wheel",
+            &catalogue,
+        )
+        .unwrap();
+        assert_eq!(rocket.stages.len(), 2);
+        assert_eq!(rocket.drag_area, 4.0);
+        assert_eq!((rocket.length, rocket.width), (10.0, 2.0));
+        let top = rocket.stages[0];
+        assert_eq!(top.dry_mass, 1270.0);
+        assert_eq!(top.tank.fuel, 500.0);
+        let bottom = rocket.stage();
+        assert_eq!(bottom.dry_mass, 100.0 + 100.0 + 400.0 + 10.0 + 20.0);
+        assert_eq!(bottom.tank.capacity, 1000.0);
+        assert_eq!(bottom.engine_averse.vector, dvec2(0.0, -4000.0));
+        assert_eq!(bottom.engine_averse.isp, 300.0);
+        assert_eq!(bottom.engine_left.vector, dvec2(100.0, 0.0));
+        assert_eq!(bottom.engine_right.vector, DVec2::ZERO);
+        assert_eq!(rocket.reaction_wheel_torque(), 600.0);
+        assert_eq!(bottom.engine_left.offset, dvec2(0.0, -1.0));
+
+        let mut rocket = rocket;
+        rocket.decouple();
+        assert_eq!((rocket.length, rocket.width), (4.0, 1.0));
+    }
+
+    #[test]
+    fn stages_without_a_main_engine_are_carried() {
+        let catalogue = parse_catalogue(CATALOGUE).unwrap();
+        let rocket = parse_assembly(
+            "stage length=2 width=1 pod wheel small\nstage length=3 width=1 ring small main",
+            &catalogue,
+        )
+        .unwrap();
+        assert!(!rocket.stages[0].has_main_engine());
+        let bottom = rocket.stage();
+        let ve = bottom.engine_averse.exhaust_velocity();
+        let mass = rocket.mass();
+        let delta_v = ve * (mass / (mass - bottom.tank.fuel)).ln();
+        assert!((rocket.delta_v() - delta_v).abs() < 1e-9);
+        assert!(rocket.burn_duration(0.5 * delta_v).is_finite());
+        assert_eq!(rocket.burn_duration(2.0 * delta_v), f64::INFINITY);
+
+        let mut top = rocket.clone();
+        top.decouple();
+        assert_eq!(top.delta_v(), 0.0);
+        assert_eq!(top.burn_duration(1.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn reports_bad_input_with_line_numbers() {
+        let catalogue = parse_catalogue(CATALOGUE).unwrap();
+        let missing = parse_catalogue("tank t fuel=1").unwrap_err();
+        assert_eq!(missing, "line 1: missing 'mass'");
+        let unknown = parse_assembly(
+            "stage length=1 width=1 pod\nstage length=1 width=1 main",
+            &catalogue,
+        )
+        .unwrap_err();
+        assert_eq!(unknown, "line 2: stage has no decoupler");
+        assert!(parse_assembly("stage pod", &catalogue).is_err());
+        assert!(parse_assembly("stage length=1 width=0 pod", &catalogue).is_err());
+        assert!(parse_assembly("stage pod wing", &catalogue).is_err());
+        assert!(parse_assembly("", &catalogue).is_err());
+    }
+
+    //every design in rockets/ by file name
+    fn shipped_rockets() -> Vec<(String, Result<Rocket, String>)> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(ROCKETS_DIR);
+        let catalogue = parse_catalogue(&read(&dir.join(CATALOGUE_FILE)).unwrap()).unwrap();
+        fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|e| e == ASSEMBLY_EXTENSION))
+            .map(|path| {
+                let rocket = parse_assembly(&read(&path).unwrap(), &catalogue);
+                (path.display().to_string(), rocket)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shipped_rockets_load() {
+        for (path, rocket) in shipped_rockets() {
+            if let Err(e) = rocket {
+                panic!("{}: {}", path, e);
+            }
+        }
+    }
+
+    //at full throttle on the ground of the heaviest generated planets
+    #[test]
+    fn shipped_rockets_lift_off_from_every_planet() {
+        let max_gravity = (0..32)
+            .flat_map(|seed| get_system(SPACE_SIZE * 0.5, seed))
+            .filter(|(c_body, _)| c_body.b_type == CelestialBodyType::Planet)
+            .map(|(c_body, n_body)| G * n_body.mass / (c_body.radius * c_body.radius))
+            .fold(0.0, f64::max);
+        for (path, rocket) in shipped_rockets() {
+            let rocket = rocket.unwrap();
+            let weight = rocket.mass() * max_gravity;
+            let thrust_to_weight = rocket.stage().engine_averse.vector.length() / weight;
+            assert!(
+                thrust_to_weight > 1.0,
+                "{}: thrust to weight {:.2} at {:.1} m/s2",
+                path,
+                thrust_to_weight,
+                max_gravity
+            );
+        }
+    }
+}
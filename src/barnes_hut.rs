@@ -0,0 +1,241 @@
+use glam::DVec2;
+
+use crate::gravity::{point_acceleration, PointMass};
+
+//bodies closer than root_size / 2^MAX_DEPTH are merged into one leaf
+const MAX_DEPTH: usize = 48;
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    center: DVec2,
+    half_size: f64,
+    count: usize,
+    mass: f64,
+    //mass weighted position sum while building, center of mass afterwards
+    com: DVec2,
+    //index of the first of four consecutive children
+    children: Option<usize>,
+    body: Option<usize>,
+}
+
+impl Node {
+    fn new(center: DVec2, half_size: f64) -> Self {
+        Node {
+            center,
+            half_size,
+            count: 0,
+            mass: 0.0,
+            com: DVec2::ZERO,
+            children: None,
+            body: None,
+        }
+    }
+
+    fn add(&mut self, body: &PointMass) {
+        self.count += 1;
+        self.mass += body.mass;
+        self.com += body.pos * body.mass;
+    }
+
+    fn contains(&self, p: DVec2) -> bool {
+        (p - self.center).abs().max_element() <= self.half_size
+    }
+
+    //0 - left top, 1 - right top, 2 - left bottom, 3 - right bottom
+    fn quadrant(&self, p: DVec2) -> usize {
+        (p.x >= self.center.x) as usize + 2 * (p.y >= self.center.y) as usize
+    }
+}
+
+pub struct QuadTree<'a> {
+    nodes: Vec<Node>,
+    sources: &'a [PointMass],
+}
+
+impl<'a> QuadTree<'a> {
+    pub fn new(sources: &'a [PointMass]) -> Self {
+        let (lt, rb) = sources.iter().fold(
+            (DVec2::splat(f64::INFINITY), DVec2::splat(f64::NEG_INFINITY)),
+            |(lt, rb), s| (lt.min(s.pos), rb.max(s.pos)),
+        );
+        let (center, half_size) = if sources.is_empty() {
+            (DVec2::ZERO, 1.0)
+        } else {
+            //slightly enlarged so bodies on the boundary stay inside
+            (
+                lt.midpoint(rb),
+                ((rb - lt).max_element() * 0.5).max(1.0) * 1.0001,
+            )
+        };
+
+        let mut tree = QuadTree {
+            nodes: Vec::with_capacity(sources.len() * 2 + 1),
+            sources,
+        };
+        tree.nodes.push(Node::new(center, half_size));
+        for i in 0..sources.len() {
+            tree.insert(i);
+        }
+        for node in tree.nodes.iter_mut() {
+            node.com = match node.body {
+                //exact position, so the body is recognized as self interaction
+                Some(b) if node.count == 1 => sources[b].pos,
+                _ if node.mass > 0.0 => node.com / node.mass,
+                _ => node.center,
+            };
+        }
+        tree
+    }
+
+    fn insert(&mut self, index: usize) {
+        let body = self.sources[index];
+        let mut node = 0;
+        let mut depth = 0;
+        loop {
+            if let Some(first) = self.nodes[node].children {
+                self.nodes[node].add(&body);
+                node = first + self.nodes[node].quadrant(body.pos);
+                depth += 1;
+                continue;
+            }
+            match self.nodes[node].body {
+                None => {
+                    self.nodes[node].body = Some(index);
+                    self.nodes[node].add(&body);
+                    return;
+                }
+                Some(_) if depth >= MAX_DEPTH => {
+                    self.nodes[node].add(&body);
+                    return;
+                }
+                Some(resident) => {
+                    let first = self.subdivide(node);
+                    let resident_body = self.sources[resident];
+                    let child = first + self.nodes[node].quadrant(resident_body.pos);
+                    self.nodes[child].body = Some(resident);
+                    self.nodes[child].add(&resident_body);
+                    self.nodes[node].body = None;
+                }
+            }
+        }
+    }
+
+    fn subdivide(&mut self, node: usize) -> usize {
+        let Node {
+            center, half_size, ..
+        } = self.nodes[node];
+        let q = half_size * 0.5;
+        let first = self.nodes.len();
+        for offset in [
+            DVec2::new(-q, -q),
+            DVec2::new(q, -q),
+            DVec2::new(-q, q),
+            DVec2::new(q, q),
+        ] {
+            self.nodes.push(Node::new(center + offset, q));
+        }
+        self.nodes[node].children = Some(first);
+        first
+    }
+
+    //node is approximated by its center of mass when size / distance < theta,
+    //a node containing `at` is always opened so a body never attracts itself
+    //through the center of mass of its parent
+    pub fn acceleration(&self, at: DVec2, theta: f64, softening: f64) -> DVec2 {
+        let theta_sq = theta * theta;
+        let mut acc = DVec2::ZERO;
+        let mut stack = vec![0];
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            if node.mass == 0.0 {
+                continue;
+            }
+            match node.children {
+                Some(first)
+                    if node.contains(at)
+                        || (2.0 * node.half_size).powi(2)
+                            >= theta_sq * node.com.distance_squared(at) =>
+                {
+                    stack.extend(first..first + 4);
+                }
+                _ => acc += point_acceleration(at, node.com, node.mass, softening),
+            }
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::gravity::GravitySolver;
+
+    fn cluster(count: usize) -> Vec<PointMass> {
+        let mut rng = StdRng::seed_from_u64(1);
+        (0..count)
+            .map(|_| PointMass {
+                pos: DVec2::new(rng.random_range(-1e9..1e9), rng.random_range(-1e9..1e9)),
+                mass: rng.random_range(1e22..1e25),
+            })
+            .collect()
+    }
+
+    //largest error against the rms acceleration, single bodies can sit where
+    //the forces cancel and their own relative error is meaningless
+    fn max_relative_error(sources: &[PointMass], theta: f64) -> f64 {
+        let targets: Vec<_> = sources.iter().map(|s| s.pos).collect();
+        let direct = GravitySolver::Direct.accelerations(sources, &targets, 0.0);
+        let tree = GravitySolver::BarnesHut { theta }.accelerations(sources, &targets, 0.0);
+        let rms =
+            (direct.iter().map(|d| d.length_squared()).sum::<f64>() / direct.len() as f64).sqrt();
+        direct
+            .iter()
+            .zip(&tree)
+            .map(|(d, t)| d.distance(*t) / rms)
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn barnes_hut_matches_direct_summation() {
+        let sources = cluster(300);
+        let error = max_relative_error(&sources, 0.5);
+        assert!(error < 0.01, "relative error {}", error);
+    }
+
+    #[test]
+    fn zero_theta_is_exact() {
+        let sources = cluster(100);
+        assert!(max_relative_error(&sources, 0.0) < 1e-12);
+    }
+
+    #[test]
+    fn body_does_not_attract_itself_at_large_theta() {
+        //with the node of the close pair approximated the first body would be
+        //pulled towards the common center of mass by its own mass
+        let sources = [
+            PointMass {
+                pos: DVec2::new(0.0, 0.0),
+                mass: 1e30,
+            },
+            PointMass {
+                pos: DVec2::new(1e3, 0.0),
+                mass: 1e30,
+            },
+            PointMass {
+                pos: DVec2::new(1e9, 1e9),
+                mass: 1.0,
+            },
+        ];
+        let tree = QuadTree::new(&sources);
+        let acc = tree.acceleration(sources[0].pos, 10.0, 0.0);
+        let exact = GravitySolver::Direct.accelerations(&sources, &[sources[0].pos], 0.0)[0];
+        assert!(
+            acc.distance(exact) <= 1e-9 * exact.length().max(1e-30),
+            "{} != {}",
+            acc,
+            exact
+        );
+    }
+}
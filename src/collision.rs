@@ -0,0 +1,300 @@
+use glam::{dvec2, DVec2};
+
+pub fn triangle_aabb(p1: DVec2, p2: DVec2, p3: DVec2) -> (DVec2, DVec2) {
+    (
+        dvec2(p1.x.min(p2.x).min(p3.x), p1.y.min(p2.y).min(p3.y)),
+        dvec2(p1.x.max(p2.x).max(p3.x), p1.y.max(p2.y).max(p3.y)),
+    )
+}
+pub fn circle_aabb(c_center: DVec2, c_radius: f64) -> (DVec2, DVec2) {
+    let disp = DVec2::splat(c_radius);
+    (c_center - disp, c_center + disp)
+}
+
+pub fn is_aabb_intersected(a_lt: DVec2, a_rb: DVec2, b_lt: DVec2, b_rb: DVec2) -> bool {
+    #[inline(always)]
+    fn rb_from(a: DVec2, b: DVec2) -> bool {
+        a.x >= b.x && a.y >= b.y
+    }
+    rb_from(a_rb, b_lt) && rb_from(b_rb, a_lt)
+}
+
+pub fn is_circles_intersected(
+    a_center: DVec2,
+    a_radius: f64,
+    b_center: DVec2,
+    b_radius: f64,
+) -> bool {
+    a_center.distance_squared(b_center) <= (a_radius + b_radius).powi(2)
+}
+
+pub fn is_point_in_circle(p: DVec2, c_center: DVec2, c_radius: f64) -> bool {
+    p.distance(c_center) <= c_radius
+}
+
+pub fn is_segment_intersects_circle(
+    seg_p1: DVec2,
+    seg_p2: DVec2,
+    c_center: DVec2,
+    c_radius: f64,
+) -> bool {
+    is_point_in_circle(seg_p1, c_center, c_radius)
+        || is_point_in_circle(seg_p2, c_center, c_radius)
+        || is_segment_intersects_circle_perp_method(seg_p1, seg_p2, c_center, c_radius)
+}
+
+//fraction of the way from `seg_p1` to `seg_p2` where the segment enters the
+//circle, None if it misses it or starts inside, a start on the circle is an
+//impact at 0 when it heads in
+pub fn segment_circle_time_of_impact(
+    seg_p1: DVec2,
+    seg_p2: DVec2,
+    c_center: DVec2,
+    c_radius: f64,
+) -> Option<f64> {
+    let d = seg_p2 - seg_p1;
+    let f = seg_p1 - c_center;
+    let a = d.length_squared();
+    let b = f.dot(d);
+    let c = f.length_squared() - c_radius * c_radius;
+    let discriminant = b * b - a * c;
+    if c < 0.0 || a == 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / a;
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+//true iff segment intersect circle in two points
+pub fn is_segment_intersects_circle_perp_method(
+    seg_p1: DVec2,
+    seg_p2: DVec2,
+    c_center: DVec2,
+    c_radius: f64,
+) -> bool {
+    //squared length of segment
+    let len = seg_p1.distance_squared(seg_p2);
+    //dot product to form closest perpendicular intersection point with line on which segment lies
+    let dot = (c_center - seg_p1).dot(seg_p2 - seg_p1) / len;
+    //closest point to circle center on segment (may be off segment, but on line with it)
+    let closest: DVec2 = seg_p1 + dot * (seg_p2 - seg_p1);
+    //intersect or on tangent line to circle
+    closest.distance_squared(seg_p1) + closest.distance_squared(seg_p2) <= len
+        && c_radius >= c_center.distance(closest)
+}
+
+//true iff `p` is on the inner side of an edge of a counterclockwise outline,
+//i.e. on the left of the line through the segment
+pub fn is_point_behind_segment(p: DVec2, seg_p1: DVec2, seg_p2: DVec2) -> bool {
+    (seg_p2 - seg_p1).perp_dot(p - seg_p1) >= 0.0
+}
+
+//unit normal pointing out of a counterclockwise outline
+pub fn segment_normal(seg_p1: DVec2, seg_p2: DVec2) -> DVec2 {
+    -(seg_p2 - seg_p1).perp().normalize_or_zero()
+}
+
+//true iff the segments cross or touch
+pub fn is_segments_intersected(a1: DVec2, a2: DVec2, b1: DVec2, b2: DVec2) -> bool {
+    let a = a2 - a1;
+    let b = b2 - b1;
+    let d1 = a.perp_dot(b1 - a1);
+    let d2 = a.perp_dot(b2 - a1);
+    let d3 = b.perp_dot(a1 - b1);
+    let d4 = b.perp_dot(a2 - b1);
+    if d1 == 0.0 && d2 == 0.0 {
+        //on the same line, the projections have to overlap
+        let (t1, t2) = (a.dot(b1 - a1), a.dot(b2 - a1));
+        return t1.max(t2) >= 0.0 && t1.min(t2) <= a.length_squared();
+    }
+    d1 * d2 <= 0.0 && d3 * d4 <= 0.0
+}
+
+//true iff `p` is inside or on the edge of the triangle, in either winding
+pub fn is_point_in_triangle(p: DVec2, t1: DVec2, t2: DVec2, t3: DVec2) -> bool {
+    let d1 = (t2 - t1).perp_dot(p - t1);
+    let d2 = (t3 - t2).perp_dot(p - t2);
+    let d3 = (t1 - t3).perp_dot(p - t3);
+    let negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(negative && positive)
+}
+
+//true iff two edges cross or one triangle is inside the other
+pub fn is_triangles_intersected(a: &[DVec2; 3], b: &[DVec2; 3]) -> bool {
+    (0..3).any(|i| {
+        (0..3).any(|j| is_segments_intersected(a[i], a[(i + 1) % 3], b[j], b[(j + 1) % 3]))
+    }) || is_point_in_triangle(a[0], b[0], b[1], b[2])
+        || is_point_in_triangle(b[0], a[0], a[1], a[2])
+}
+
+//shortest move of `b` along an edge normal that separates it from `a`, as the
+//unit direction and the depth, None if they do not overlap
+pub fn triangles_penetration(a: &[DVec2; 3], b: &[DVec2; 3]) -> Option<(DVec2, f64)> {
+    let project = |t: &[DVec2; 3], axis: DVec2| {
+        t.iter()
+            .map(|p| p.dot(axis))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), d| {
+                (min.min(d), max.max(d))
+            })
+    };
+    let mut best: Option<(DVec2, f64)> = None;
+    for t in [a, b] {
+        for i in 0..3 {
+            let axis = (t[(i + 1) % 3] - t[i]).perp().normalize_or_zero();
+            if axis == DVec2::ZERO {
+                continue;
+            }
+            let (a_min, a_max) = project(a, axis);
+            let (b_min, b_max) = project(b, axis);
+            //out on whichever side is nearer
+            let (normal, depth) = if a_max - b_min < b_max - a_min {
+                (axis, a_max - b_min)
+            } else {
+                (-axis, b_max - a_min)
+            };
+            if depth < 0.0 {
+                return None;
+            }
+            if best.is_none_or(|(_, d)| depth < d) {
+                best = Some((normal, depth));
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use super::*;
+
+    fn toi(p1: DVec2, p2: DVec2) -> Option<f64> {
+        segment_circle_time_of_impact(p1, p2, DVec2::ZERO, 1.0)
+    }
+
+    #[test]
+    fn time_of_impact_misses() {
+        assert_eq!(toi(dvec2(-2.0, 2.0), dvec2(2.0, 2.0)), None);
+        //heading at the circle, but stopping short of it
+        assert_eq!(toi(dvec2(-3.0, 0.0), dvec2(-2.0, 0.0)), None);
+        assert_eq!(toi(dvec2(3.0, 0.0), dvec2(3.0, 0.0)), None);
+    }
+
+    #[test]
+    fn time_of_impact_tangent() {
+        assert_eq!(toi(dvec2(-2.0, 1.0), dvec2(2.0, 1.0)), Some(0.5));
+    }
+
+    #[test]
+    fn time_of_impact_starting_inside() {
+        assert_eq!(toi(DVec2::ZERO, dvec2(2.0, 0.0)), None);
+        assert_eq!(toi(dvec2(0.5, 0.0), dvec2(-0.5, 0.0)), None);
+    }
+
+    #[test]
+    fn time_of_impact_at_the_ends() {
+        assert_eq!(toi(dvec2(1.0, 0.0), dvec2(-1.0, 0.0)), Some(0.0));
+        //leaving from the circle is not an impact
+        assert_eq!(toi(dvec2(1.0, 0.0), dvec2(2.0, 0.0)), None);
+        assert_eq!(toi(dvec2(3.0, 0.0), dvec2(1.0, 0.0)), Some(1.0));
+    }
+
+    #[test]
+    fn time_of_impact_through_a_planet() {
+        let radius = 6e6;
+        let p1 = dvec2(-1e7, 3e6);
+        let p2 = dvec2(1e7, 3e6);
+        let t = segment_circle_time_of_impact(p1, p2, DVec2::ZERO, radius).unwrap();
+        //the entry on the near side, not the exit
+        let entry = p1.lerp(p2, t);
+        assert!(entry.x < 0.0);
+        assert!((entry.length() - radius).abs() < 1e-6);
+    }
+
+    const UNIT: [DVec2; 3] = [DVec2::ZERO, DVec2::X, DVec2::Y];
+
+    fn moved(t: [DVec2; 3], by: DVec2) -> [DVec2; 3] {
+        t.map(|p| p + by)
+    }
+
+    #[test]
+    fn segments_intersect() {
+        let (a1, a2) = (DVec2::ZERO, dvec2(2.0, 2.0));
+        assert!(is_segments_intersected(
+            a1,
+            a2,
+            dvec2(0.0, 2.0),
+            dvec2(2.0, 0.0)
+        ));
+        //touching at an end
+        assert!(is_segments_intersected(
+            a1,
+            a2,
+            dvec2(1.0, 1.0),
+            dvec2(2.0, 0.0)
+        ));
+        assert!(!is_segments_intersected(
+            a1,
+            a2,
+            dvec2(1.0, 0.0),
+            dvec2(2.0, 0.0)
+        ));
+        //collinear, overlapping and apart
+        assert!(is_segments_intersected(
+            a1,
+            a2,
+            dvec2(1.0, 1.0),
+            dvec2(3.0, 3.0)
+        ));
+        assert!(!is_segments_intersected(
+            a1,
+            a2,
+            dvec2(3.0, 3.0),
+            dvec2(4.0, 4.0)
+        ));
+    }
+
+    #[test]
+    fn points_in_triangle() {
+        let [t1, t2, t3] = UNIT;
+        assert!(is_point_in_triangle(dvec2(0.25, 0.25), t1, t2, t3));
+        assert!(is_point_in_triangle(dvec2(0.25, 0.25), t1, t3, t2));
+        assert!(is_point_in_triangle(dvec2(0.5, 0.5), t1, t2, t3));
+        assert!(!is_point_in_triangle(dvec2(0.75, 0.75), t1, t2, t3));
+        assert!(!is_point_in_triangle(dvec2(-0.1, 0.5), t1, t2, t3));
+    }
+
+    #[test]
+    fn triangles_intersect() {
+        assert!(is_triangles_intersected(
+            &UNIT,
+            &moved(UNIT, dvec2(0.25, 0.25))
+        ));
+        assert!(!is_triangles_intersected(
+            &UNIT,
+            &moved(UNIT, dvec2(1.0, 1.0))
+        ));
+        //within the bounding circles of each other, but apart
+        let flipped = UNIT.map(|p| dvec2(1.0, 1.0) - p + dvec2(0.1, 0.0));
+        assert!(!is_triangles_intersected(&UNIT, &flipped));
+        //one inside the other without crossing edges
+        let big = UNIT.map(|p| p * 10.0 - dvec2(1.0, 1.0));
+        assert!(is_triangles_intersected(&big, &UNIT));
+        assert!(is_triangles_intersected(&UNIT, &big));
+    }
+
+    #[test]
+    fn penetration_separates_triangles() {
+        let b = moved(UNIT, dvec2(0.8, 0.0));
+        let (normal, depth) = triangles_penetration(&UNIT, &b).unwrap();
+        assert!(is_triangles_intersected(&UNIT, &b));
+        let apart = moved(b, normal * (depth + 1e-9));
+        assert!(!is_triangles_intersected(&UNIT, &apart));
+        assert_eq!(
+            triangles_penetration(&UNIT, &moved(UNIT, dvec2(2.0, 0.0))),
+            None
+        );
+    }
+}
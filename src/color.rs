@@ -0,0 +1,23 @@
+//display color of a body, converted to an SDL color by the renderer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const YELLOW: Color = Color::rgb(255, 255, 0);
+    pub const GREEN: Color = Color::rgb(0, 255, 0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+}
+
+#[cfg(feature = "render")]
+impl From<Color> for sdl2::pixels::Color {
+    fn from(color: Color) -> Self {
+        sdl2::pixels::Color::RGB(color.r, color.g, color.b)
+    }
+}
@@ -0,0 +1,324 @@
+use std::f64::consts::{PI, TAU};
+
+use glam::DVec2;
+
+use crate::color::Color;
+
+//points of the heightmap around the circumference
+pub const TERRAIN_SAMPLES: usize = 4096;
+pub const CRATERS: usize = 6;
+pub const LANDING_ZONES: usize = 3;
+//octaves of the mountain noise, each one twice as fine and half as high
+const TERRAIN_OCTAVES: u32 = 4;
+//height of the crater rim as a share of the depth
+const CRATER_RIM: f64 = 0.25;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CelestialBodyType {
+    Star,
+    Planet,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CelestialBody {
+    pub b_type: CelestialBodyType,
+    pub radius: f64,
+    pub color: Color,
+    pub atmosphere: Option<Atmosphere>,
+    //None for a perfectly round body
+    pub terrain: Option<Terrain>,
+}
+
+//isothermal exponential atmosphere
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Atmosphere {
+    //altitude over which the density drops by a factor of e
+    pub scale_height: f64,
+    //kg/m3 at the surface
+    pub surface_density: f64,
+    //no drag above this altitude
+    pub cutoff_altitude: f64,
+}
+
+impl Atmosphere {
+    pub fn density_at(&self, altitude: f64) -> f64 {
+        if altitude >= self.cutoff_altitude {
+            0.0
+        } else {
+            self.surface_density * (-altitude.max(0.0) / self.scale_height).exp()
+        }
+    }
+}
+
+//procedural heightmap, angles and heights are in the frame of the body
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Terrain {
+    //of the mountain noise
+    pub seed: u64,
+    //height of the highest mountains over the radius, m
+    pub amplitude: f64,
+    //mountain ranges around the circumference in the coarsest octave
+    pub ranges: u32,
+    pub craters: [Crater; CRATERS],
+    pub landing_zones: [LandingZone; LANDING_ZONES],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Crater {
+    pub angle: f64,
+    //radians
+    pub half_width: f64,
+    //m
+    pub depth: f64,
+}
+
+//stretch of the surface flattened to the height at its center
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LandingZone {
+    pub angle: f64,
+    //radians, the terrain blends back over the same width on both sides
+    pub half_width: f64,
+}
+
+impl Terrain {
+    //height of heightmap point `i`, wraps around the circumference
+    pub fn sample(&self, i: usize) -> f64 {
+        self.height_at((i % TERRAIN_SAMPLES) as f64 * TAU / TERRAIN_SAMPLES as f64)
+    }
+
+    fn height_at(&self, angle: f64) -> f64 {
+        let mut height = self.relief(angle);
+        for zone in &self.landing_zones {
+            let d = angular_distance(angle, zone.angle) / zone.half_width;
+            if d < 2.0 {
+                let flat = self.relief(zone.angle);
+                let t = smoothstep((d - 1.0).clamp(0.0, 1.0));
+                height = flat + (height - flat) * t;
+            }
+        }
+        height
+    }
+
+    //no point of the heightmap is higher
+    pub fn max_height(&self) -> f64 {
+        self.amplitude
+            + self
+                .craters
+                .iter()
+                .map(|crater| crater.depth * CRATER_RIM)
+                .sum::<f64>()
+    }
+
+    //mountains and craters
+    fn relief(&self, angle: f64) -> f64 {
+        let mut height = 0.0;
+        let mut amplitude = 0.5;
+        for octave in 0..TERRAIN_OCTAVES {
+            let cells = self.ranges << octave;
+            let u = angle.rem_euclid(TAU) / TAU * cells as f64;
+            let i = u.floor() as u32;
+            let a = lattice(self.seed, octave, i % cells);
+            let b = lattice(self.seed, octave, (i + 1) % cells);
+            height += amplitude * (a + (b - a) * smoothstep(u.fract()));
+            amplitude *= 0.5;
+        }
+        height *= self.amplitude;
+        for crater in &self.craters {
+            let d = angular_distance(angle, crater.angle) / crater.half_width;
+            if d < 1.0 {
+                height += crater.depth * ((1.0 + CRATER_RIM) * d * d - 1.0);
+            } else if d < 2.0 {
+                height += crater.depth * CRATER_RIM * (2.0 - d);
+            }
+        }
+        height
+    }
+}
+
+impl CelestialBody {
+    pub fn volume(&self) -> f64 {
+        4.0 / 3.0 * PI * self.radius.powi(3)
+    }
+
+    //uniform sphere
+    pub fn inertia(&self, mass: f64) -> f64 {
+        0.4 * mass * self.radius * self.radius
+    }
+
+    //distance from the center to the outline along the unit `direction`, in
+    //the frame of the body, a zero direction is taken along the x axis
+    pub fn surface_radius(&self, direction: DVec2) -> f64 {
+        let direction = if direction == DVec2::ZERO {
+            DVec2::X
+        } else {
+            direction
+        };
+        let (p1, p2) = self.surface_segment(direction);
+        let edge = p2 - p1;
+        p1.perp_dot(edge) / direction.perp_dot(edge)
+    }
+
+    //radius of a circle around the whole outline
+    pub fn max_surface_radius(&self) -> f64 {
+        match &self.terrain {
+            Some(terrain) => self.radius + terrain.max_height(),
+            None => self.radius,
+        }
+    }
+
+    //piece of the outline below `direction`, counterclockwise and in the
+    //frame of the body, the tangent of the circle without terrain
+    pub fn surface_segment(&self, direction: DVec2) -> (DVec2, DVec2) {
+        match &self.terrain {
+            Some(terrain) => {
+                let i = sample_index(direction.to_angle());
+                (
+                    self.outline_point(terrain, i),
+                    self.outline_point(terrain, i + 1),
+                )
+            }
+            None => {
+                let surface = direction * self.radius;
+                (surface - direction.perp(), surface + direction.perp())
+            }
+        }
+    }
+
+    //heightmap point `i` in the frame of the body
+    pub fn outline_point(&self, terrain: &Terrain, i: usize) -> DVec2 {
+        let angle = (i % TERRAIN_SAMPLES) as f64 * TAU / TERRAIN_SAMPLES as f64;
+        DVec2::from_angle(angle) * (self.radius + terrain.sample(i))
+    }
+
+    pub fn radius_for_volume(volume: f64) -> f64 {
+        (3.0 * volume / (4.0 * PI)).cbrt()
+    }
+}
+
+//heightmap point at or before `angle`
+fn sample_index(angle: f64) -> usize {
+    (angle.rem_euclid(TAU) / TAU * TERRAIN_SAMPLES as f64).floor() as usize % TERRAIN_SAMPLES
+}
+
+fn angular_distance(a: f64, b: f64) -> f64 {
+    DVec2::from_angle(a).angle_to(DVec2::from_angle(b)).abs()
+}
+
+fn smoothstep(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+//noise value in 0..1 at lattice point `i` of `octave`, splitmix64 of the inputs
+fn lattice(seed: u64, octave: u32, i: u32) -> f64 {
+    let mut z =
+        seed.wrapping_add((((octave as u64) << 32) | i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    const RADIUS: f64 = 1e6;
+
+    //landing zones are kept apart so their blends do not overlap
+    fn terrain(seed: u64) -> Terrain {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Terrain {
+            seed: rng.random(),
+            amplitude: 5e3,
+            ranges: 7,
+            craters: [(); CRATERS].map(|_| Crater {
+                angle: rng.random_range(0.0..TAU),
+                half_width: rng.random_range(0.01..0.1),
+                depth: rng.random_range(1e3..1e4),
+            }),
+            landing_zones: [0.5, 2.5, 4.5].map(|angle| LandingZone {
+                angle,
+                half_width: rng.random_range(0.01..0.05),
+            }),
+        }
+    }
+
+    fn body(terrain: Option<Terrain>) -> CelestialBody {
+        CelestialBody {
+            b_type: CelestialBodyType::Planet,
+            radius: RADIUS,
+            color: Color::GREEN,
+            atmosphere: None,
+            terrain,
+        }
+    }
+
+    fn angle_of(i: usize) -> f64 {
+        i as f64 * TAU / TERRAIN_SAMPLES as f64
+    }
+
+    #[test]
+    fn max_height_bounds_every_sample() {
+        for seed in 0..16 {
+            let terrain = terrain(seed);
+            let max = terrain.max_height();
+            for i in 0..TERRAIN_SAMPLES {
+                assert!(terrain.sample(i) <= max, "seed {} sample {}", seed, i);
+            }
+        }
+    }
+
+    #[test]
+    fn round_body_surface_is_the_radius() {
+        let body = body(None);
+        for i in 0..64 {
+            let direction = DVec2::from_angle(i as f64 * TAU / 64.0);
+            assert!((body.surface_radius(direction) - RADIUS).abs() < 1e-6);
+        }
+        assert_eq!(body.max_surface_radius(), RADIUS);
+    }
+
+    #[test]
+    fn zero_direction_has_a_surface() {
+        assert!((body(None).surface_radius(DVec2::ZERO) - RADIUS).abs() < 1e-6);
+        let body = body(Some(terrain(0)));
+        assert_eq!(
+            body.surface_radius(DVec2::ZERO),
+            body.surface_radius(DVec2::X)
+        );
+    }
+
+    #[test]
+    fn landing_zones_are_flat() {
+        for seed in 0..16 {
+            let terrain = terrain(seed);
+            for zone in &terrain.landing_zones {
+                let heights: Vec<_> = (0..TERRAIN_SAMPLES)
+                    .filter(|i| angular_distance(angle_of(*i), zone.angle) < zone.half_width)
+                    .map(|i| terrain.sample(i))
+                    .collect();
+                assert!(heights.len() > 1, "seed {}", seed);
+                assert!(
+                    heights.iter().all(|h| *h == heights[0]),
+                    "seed {} zone at {}",
+                    seed,
+                    zone.angle
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sample_index_wraps_at_tau() {
+        assert_eq!(sample_index(0.0), 0);
+        assert_eq!(sample_index(TAU), 0);
+        assert_eq!(sample_index(TAU - 1e-9), TERRAIN_SAMPLES - 1);
+        assert_eq!(sample_index(-1e-9), TERRAIN_SAMPLES - 1);
+        //halfway between points, away from rounding at the boundaries
+        let angle = 5.5 * TAU / TERRAIN_SAMPLES as f64;
+        assert_eq!(sample_index(angle + 3.0 * TAU), 5);
+        assert_eq!(sample_index(angle - TAU), 5);
+    }
+}
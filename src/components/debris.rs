@@ -0,0 +1,8 @@
+use glam::DVec2;
+
+//wreckage of a crashed rocket, gone once it hits the ground again
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Debris {
+    //in the frame of the body, see Rocket::hull
+    pub hull: [DVec2; 3],
+}
@@ -0,0 +1,13 @@
+use uom::si::{f64::Length, f64::Velocity};
+
+use crate::orbit::OrbitalElements;
+
+pub struct FlightInfo {
+    pub delta: Velocity,
+    pub distance: Length,
+    //orbit around the closest celestial body
+    pub orbit: Option<OrbitalElements>,
+    //above the surface, apoapsis is None for open orbits
+    pub periapsis: Option<Length>,
+    pub apoapsis: Option<Length>,
+}
@@ -0,0 +1,20 @@
+use glam::DVec2;
+use legion::Entity;
+
+//both directions are relative to the rotation of the planet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LandingRelation {
+    pub planet_id: Entity,
+    //from the center of the planet to the rocket
+    pub angle_position: DVec2,
+    //orientation of the rocket
+    pub attitude: DVec2,
+}
+
+//left by a hard landing
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Damaged;
+
+//the rocket is wrecked and stays on the surface until the flight is reverted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Crashed;
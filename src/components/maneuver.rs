@@ -0,0 +1,130 @@
+use glam::DVec2;
+
+use crate::components::trajectory::Trajectory;
+
+//planned impulsive burn in the frame of the velocity at the node, in 2D the
+//orbit normal points out of the plane so the in-plane normal is the only axis
+//square to prograde, radial shares it and only picks the side away from the
+//reference body
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManeuverNode {
+    //simulation time of the middle of the burn
+    pub time: f64,
+    //along the velocity
+    pub prograde: f64,
+    //along the velocity turned counterclockwise
+    pub normal: f64,
+    //along the in-plane normal, positive on the side away from the reference
+    //body
+    pub radial: f64,
+    //the rest is updated by the maneuver system
+    //relative to the reference body
+    pub position: DVec2,
+    //delta-v in world space
+    pub burn: DVec2,
+    //at full thrust of the main engine
+    pub burn_duration: f64,
+    //seconds from now until the burn has to start
+    pub burn_in: f64,
+    //orbit after the burn, marker times are relative to the node
+    pub trajectory: Trajectory,
+}
+
+impl ManeuverNode {
+    pub fn new(time: f64) -> Self {
+        ManeuverNode {
+            time,
+            prograde: 0.0,
+            normal: 0.0,
+            radial: 0.0,
+            position: DVec2::ZERO,
+            burn: DVec2::ZERO,
+            burn_duration: 0.0,
+            burn_in: 0.0,
+            trajectory: Trajectory::default(),
+        }
+    }
+
+    //sum of the components at `pos` and `vel` relative to the reference body
+    pub fn burn_at(&self, pos: DVec2, vel: DVec2) -> DVec2 {
+        let prograde = vel.normalize_or_zero();
+        let normal = prograde.perp();
+        let radial = if normal.dot(pos) < 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        prograde * self.prograde + normal * self.normal + radial * self.radial
+    }
+
+    pub fn delta_v(&self) -> f64 {
+        self.burn.length()
+    }
+
+    pub fn burn_direction(&self) -> DVec2 {
+        self.burn.normalize_or_zero()
+    }
+
+    //the burn is centered on the node
+    pub fn burn_start(&self) -> f64 {
+        self.time - 0.5 * self.burn_duration
+    }
+
+    pub fn burn_end(&self) -> f64 {
+        self.time + 0.5 * self.burn_duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use super::*;
+
+    fn node(prograde: f64, normal: f64, radial: f64) -> ManeuverNode {
+        ManeuverNode {
+            prograde,
+            normal,
+            radial,
+            ..ManeuverNode::new(0.0)
+        }
+    }
+
+    #[test]
+    fn burn_is_the_sum_of_the_components() {
+        //off the apses of an eccentric orbit, the position is not square to
+        //the velocity
+        let pos = dvec2(3.0, 1.0);
+        let vel = dvec2(-0.5, 2.0);
+        let prograde = vel.normalize();
+        let normal = prograde.perp();
+        let radial = normal * normal.dot(pos).signum();
+
+        let burn = node(10.0, -4.0, 7.0).burn_at(pos, vel);
+        let sum = prograde * 10.0 - normal * 4.0 + radial * 7.0;
+        assert!(burn.distance(sum) < 1e-12);
+        assert!((burn.dot(prograde) - 10.0).abs() < 1e-12);
+        for (components, expected) in [
+            (node(10.0, 0.0, 0.0), prograde * 10.0),
+            (node(0.0, -4.0, 0.0), normal * -4.0),
+            (node(0.0, 0.0, 7.0), radial * 7.0),
+        ] {
+            assert!(components.burn_at(pos, vel).distance(expected) < 1e-12);
+        }
+    }
+
+    #[test]
+    fn radial_points_away_from_the_body() {
+        let vel = dvec2(0.0, 1.0);
+        for pos in [dvec2(5.0, -2.0), dvec2(-5.0, 3.0)] {
+            assert!(node(0.0, 0.0, 1.0).burn_at(pos, vel).dot(pos) > 0.0);
+        }
+    }
+
+    #[test]
+    fn delta_v_is_the_length_of_the_applied_burn() {
+        let mut node = node(3.0, 4.0, 0.0);
+        node.burn = node.burn_at(dvec2(1.0, 0.0), dvec2(0.0, 1.0));
+        assert!((node.delta_v() - 5.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,14 @@
+pub mod celestial_body;
+pub mod debris;
+pub mod flight_info;
+pub mod landing;
+pub mod maneuver;
+pub mod newton_body;
+pub mod on_rails;
+pub mod reference_body;
+pub mod rocket;
+pub mod sas;
+pub mod spent_stage;
+pub mod sphere_of_influence;
+pub mod test_particle;
+pub mod trajectory;
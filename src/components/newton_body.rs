@@ -0,0 +1,61 @@
+use glam::{dvec2, DVec2};
+
+use crate::Dt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NewtonBody {
+    pub mass: f64,
+    pub pos: DVec2,
+    pub acc: DVec2,
+    pub vel: DVec2,
+    pub angle: DVec2,
+    pub angular_vel: f64,
+    //moment of inertia around the center of mass, kg m2
+    pub inertia: f64,
+    //external forces (thrust, drag) accumulated during the current step
+    pub force: DVec2,
+    //external torque accumulated during the current step, positive rotates
+    //counterclockwise like `angular_vel`
+    pub torque: f64,
+    //state at the beginning of the current physics step
+    pub prev_pos: DVec2,
+    pub prev_angle: DVec2,
+}
+
+impl NewtonBody {
+    //semi-implicit Euler, the torque is held constant over the step
+    pub fn update_a(&mut self, dt: &Dt) {
+        if self.inertia > 0.0 {
+            self.angular_vel += self.torque / self.inertia * dt.0;
+        }
+        self.angle = self
+            .angle
+            .rotate(DVec2::from_angle(self.angular_vel * dt.0));
+    }
+
+    pub fn begin_step(&mut self) {
+        self.store_previous();
+        self.force = DVec2::ZERO;
+        self.torque = 0.0;
+    }
+
+    pub fn store_previous(&mut self) {
+        self.prev_pos = self.pos;
+        self.prev_angle = self.angle;
+    }
+
+    //rotates a world vector into the frame of the body
+    pub fn to_local(&self, v: DVec2) -> DVec2 {
+        dvec2(self.angle.x, -self.angle.y).rotate(v)
+    }
+
+    pub fn interpolated_pos(&self, alpha: f64) -> DVec2 {
+        self.prev_pos.lerp(self.pos, alpha)
+    }
+
+    pub fn interpolated_angle(&self, alpha: f64) -> DVec2 {
+        self.prev_angle.rotate(DVec2::from_angle(
+            self.prev_angle.angle_to(self.angle) * alpha,
+        ))
+    }
+}
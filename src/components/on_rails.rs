@@ -0,0 +1,13 @@
+use legion::Entity;
+
+use crate::orbit::OrbitalElements;
+
+//body moved analytically instead of being integrated
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OnRails {
+    Fixed,
+    Orbit {
+        parent: Entity,
+        elements: OrbitalElements,
+    },
+}
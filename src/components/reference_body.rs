@@ -1,7 +1,9 @@
 use glam::DVec2;
 use legion::Entity;
 
-pub struct ClosestCelestialBody {
+//celestial body whose sphere of influence the entity is in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReferenceBody {
     pub id: Entity,
     pub closest_surface_point: DVec2,
     pub closest_surface_point_a: DVec2,
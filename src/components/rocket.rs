@@ -0,0 +1,293 @@
+use core::f64;
+
+use glam::{dvec2, DVec2};
+
+use crate::components::sas::SasMode;
+
+//converts specific impulse to exhaust velocity, m/s2
+pub const STANDARD_GRAVITY: f64 = 9.80665;
+//used when an assembly does not specify its drag
+pub const DRAG_COEFFICIENT: f64 = 0.75;
+//cross section facing the flow, m2
+pub const DRAG_AREA: f64 = 80.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Engine {
+    pub vector: DVec2,
+    pub throttle: f64,
+    pub isp: f64,
+    //where the thrust is applied relative to the center of mass, m
+    pub offset: DVec2,
+}
+
+const THROTTLE_MIN: f64 = 0.0;
+const THROTTLE_MAX: f64 = 1.0;
+
+impl Engine {
+    pub fn trust(self) -> DVec2 {
+        self.vector * self.throttle
+    }
+
+    pub fn exhaust_velocity(self) -> f64 {
+        self.isp * STANDARD_GRAVITY
+    }
+
+    //propellant burnt at the current throttle, kg/s
+    pub fn mass_flow(self) -> f64 {
+        self.trust().length() / self.exhaust_velocity()
+    }
+
+    pub fn change_throttle(&mut self, t: f64) {
+        self.set_throttle(self.throttle + t);
+    }
+
+    pub fn set_throttle(&mut self, t: f64) {
+        self.throttle = t.clamp(THROTTLE_MIN, THROTTLE_MAX);
+    }
+
+    pub fn full(&mut self) {
+        self.throttle = THROTTLE_MAX;
+    }
+
+    pub fn disable(&mut self) {
+        self.throttle = THROTTLE_MIN;
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FuelTank {
+    pub fuel: f64,
+    pub capacity: f64,
+}
+
+impl FuelTank {
+    pub fn full(capacity: f64) -> Self {
+        FuelTank {
+            fuel: capacity,
+            capacity,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fuel <= 0.0
+    }
+
+    //takes up to `amount` and returns how much was there
+    pub fn drain(&mut self, amount: f64) -> f64 {
+        let drained = amount.min(self.fuel);
+        self.fuel -= drained;
+        drained
+    }
+}
+
+//engines, tanks and structure dropped together when decoupled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stage {
+    pub engine_averse: Engine,
+    pub engine_reverse: Engine,
+    pub engine_left: Engine,
+    pub engine_right: Engine,
+    pub dry_mass: f64,
+    pub tank: FuelTank,
+    //maximum torque of the reaction wheels, N m
+    pub reaction_wheel_torque: f64,
+    //along and across the thrust axis, m
+    pub length: f64,
+    pub width: f64,
+}
+
+impl Stage {
+    pub fn mass(&self) -> f64 {
+        self.dry_mass + self.tank.fuel
+    }
+
+    //see Rocket::hull
+    pub fn hull(&self) -> [DVec2; 3] {
+        hull(self.length, self.width)
+    }
+
+    //uniform rectangle around its center
+    pub fn inertia(&self) -> f64 {
+        self.mass() * (self.length * self.length + self.width * self.width) / 12.0
+    }
+
+    //without one the stage is only carried, its isp is a placeholder
+    pub fn has_main_engine(&self) -> bool {
+        self.engine_averse.vector != DVec2::ZERO
+    }
+
+    pub fn engines(self) -> [Engine; 4] {
+        [
+            self.engine_averse,
+            self.engine_reverse,
+            self.engine_left,
+            self.engine_right,
+        ]
+    }
+
+    pub fn mass_flow(self) -> f64 {
+        self.engines().iter().map(|e| e.mass_flow()).sum()
+    }
+
+    pub fn trust(self) -> DVec2 {
+        self.engines().iter().map(|e| e.trust()).sum()
+    }
+
+    //of the engines around the center of mass
+    pub fn torque(self) -> f64 {
+        self.engines()
+            .iter()
+            .map(|e| e.offset.perp_dot(e.trust()))
+            .sum()
+    }
+
+    pub fn shutdown(&mut self) {
+        self.engine_averse.disable();
+        self.engine_reverse.disable();
+        self.engine_left.disable();
+        self.engine_right.disable();
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rocket {
+    //top first, the last one is the burning stage
+    pub stages: Vec<Stage>,
+    pub drag_coefficient: f64,
+    pub drag_area: f64,
+    //hull along and across the thrust axis of the attached stages, m
+    pub length: f64,
+    pub width: f64,
+}
+
+impl Rocket {
+    pub fn stage(&self) -> &Stage {
+        self.stages.last().unwrap()
+    }
+
+    pub fn stage_mut(&mut self) -> &mut Stage {
+        self.stages.last_mut().unwrap()
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.stages.iter().map(|s| s.mass()).sum()
+    }
+
+    //triangle around the hull in the frame of the body, the base corners are
+    //aft of the center of mass and the nose is forward
+    pub fn hull(&self) -> [DVec2; 3] {
+        hull(self.length, self.width)
+    }
+
+    //uniform rectangle around its center
+    pub fn inertia(&self) -> f64 {
+        self.mass() * (self.length * self.length + self.width * self.width) / 12.0
+    }
+
+    //reaction wheels work in every attached stage, engines only in the burning one
+    pub fn reaction_wheel_torque(&self) -> f64 {
+        self.stages.iter().map(|s| s.reaction_wheel_torque).sum()
+    }
+
+    pub fn mass_flow(&self) -> f64 {
+        self.stage().mass_flow()
+    }
+
+    pub fn trust(&self) -> DVec2 {
+        self.stage().trust()
+    }
+
+    //propellant is flowing, a throttled engine on an empty tank does nothing
+    pub fn is_thrusting(&self) -> bool {
+        !self.stage().tank.is_empty() && self.mass_flow() > 0.0
+    }
+
+    //rocket equation summed over the stages still attached
+    pub fn delta_v(&self) -> f64 {
+        let mut mass = self.mass();
+        let mut delta_v = 0.0;
+        for stage in self.stages.iter().rev() {
+            if stage.has_main_engine() {
+                delta_v +=
+                    stage.engine_averse.exhaust_velocity() * (mass / (mass - stage.tank.fuel)).ln();
+            }
+            mass -= stage.mass();
+        }
+        delta_v
+    }
+
+    //main engines at full throttle staging without delay, infinite if there is
+    //not enough fuel
+    pub fn burn_duration(&self, mut delta_v: f64) -> f64 {
+        let mut mass = self.mass();
+        let mut duration = 0.0;
+        for stage in self.stages.iter().rev() {
+            if !stage.has_main_engine() {
+                mass -= stage.mass();
+                continue;
+            }
+            let exhaust_velocity = stage.engine_averse.exhaust_velocity();
+            let full_flow = stage.engine_averse.vector.length() / exhaust_velocity;
+            let stage_delta_v = exhaust_velocity * (mass / (mass - stage.tank.fuel)).ln();
+            if delta_v <= stage_delta_v {
+                return duration + mass * (1.0 - (-delta_v / exhaust_velocity).exp()) / full_flow;
+            }
+            duration += stage.tank.fuel / full_flow;
+            delta_v -= stage_delta_v;
+            mass -= stage.mass();
+        }
+        f64::INFINITY
+    }
+
+    //drops the burning stage, the last one stays; the throttle of the main
+    //engine carries over and the hull shrinks to the stages left
+    pub fn decouple(&mut self) -> Option<Stage> {
+        if self.stages.len() < 2 {
+            return None;
+        }
+        let mut spent = self.stages.pop().unwrap();
+        self.length -= spent.length;
+        self.width = self.stages.iter().map(|s| s.width).fold(0.0, f64::max);
+        self.stage_mut()
+            .engine_averse
+            .set_throttle(spent.engine_averse.throttle);
+        spent.shutdown();
+        Some(spent)
+    }
+}
+
+//triangle of `length` and `width` with the base aft
+fn hull(length: f64, width: f64) -> [DVec2; 3] {
+    [
+        dvec2(-width / 2.0, length / 2.0),
+        dvec2(0.0, -length / 2.0),
+        dvec2(width / 2.0, length / 2.0),
+    ]
+}
+
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub enum PlayerInput {
+    MoveLeft,
+    MoveRight,
+    MoveForward,
+    MoveBackward,
+    RotateLeft,
+    RotateRight,
+    WindowResize(i32, i32),
+    SwitchCamera,
+    //decouples the burning stage
+    Stage,
+    //consumed by the sas system
+    ToggleSas,
+    SelectSas(SasMode),
+    CycleTarget,
+    //consumed by the maneuver system
+    ToggleManeuver,
+    ManeuverPrograde,
+    ManeuverRetrograde,
+    ManeuverNormal,
+    ManeuverAntiNormal,
+    ManeuverRadialOut,
+    ManeuverRadialIn,
+    ManeuverEarlier,
+    ManeuverLater,
+}
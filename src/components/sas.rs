@@ -0,0 +1,40 @@
+use legion::Entity;
+
+//direction the stability assist turns the nose to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SasMode {
+    KillRotation,
+    HoldHeading,
+    Prograde,
+    Retrograde,
+    RadialOut,
+    RadialIn,
+    Target,
+    Maneuver,
+}
+
+impl SasMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            SasMode::KillRotation => "KILL ROTATION",
+            SasMode::HoldHeading => "HOLD HEADING",
+            SasMode::Prograde => "PROGRADE",
+            SasMode::Retrograde => "RETROGRADE",
+            SasMode::RadialOut => "RADIAL OUT",
+            SasMode::RadialIn => "RADIAL IN",
+            SasMode::Target => "TARGET",
+            SasMode::Maneuver => "MANEUVER",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sas {
+    //None while switched off
+    pub mode: Option<SasMode>,
+    //angle of the nose held by `HoldHeading`, radians
+    pub heading: f64,
+    pub target: Option<Entity>,
+    //reaction wheel input from -1 to 1, overridden by the player
+    pub command: f64,
+}
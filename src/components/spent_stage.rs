@@ -0,0 +1,15 @@
+use glam::DVec2;
+
+use crate::components::rocket::Stage;
+
+//decoupled stage flying on its own until it hits something
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpentStage {
+    pub stage: Stage,
+    //in the frame of the body, see Rocket::hull
+    pub hull: [DVec2; 3],
+}
+
+//spent stage in its first step on its own, still inside the hull of the rocket
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Separating;
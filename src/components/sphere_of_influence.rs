@@ -0,0 +1,15 @@
+use legion::Entity;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SphereOfInfluence {
+    //None for the root of the hierarchy, usually the star
+    pub parent: Option<Entity>,
+    pub radius: f64,
+}
+
+impl SphereOfInfluence {
+    pub const ROOT: SphereOfInfluence = SphereOfInfluence {
+        parent: None,
+        radius: f64::INFINITY,
+    };
+}
@@ -0,0 +1,3 @@
+//feels gravity of massive bodies but does not attract anything itself
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestParticle;
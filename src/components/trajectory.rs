@@ -0,0 +1,28 @@
+use glam::DVec2;
+use legion::Entity;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrajectoryMarker {
+    //relative to the reference body
+    pub pos: DVec2,
+    //seconds from now
+    pub time: f64,
+}
+
+//predicted path inside the sphere of influence of the reference body
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trajectory {
+    pub reference: Option<Entity>,
+    //relative to the reference body
+    pub points: Vec<DVec2>,
+    pub apoapsis: Option<TrajectoryMarker>,
+    pub periapsis: Option<TrajectoryMarker>,
+    pub impact: Option<TrajectoryMarker>,
+    pub soi_exit: Option<TrajectoryMarker>,
+}
+
+impl Trajectory {
+    pub fn clear(&mut self) {
+        *self = Trajectory::default();
+    }
+}
@@ -0,0 +1,28 @@
+//queue of events sent by systems, it grows until the consumer drains it
+pub struct Events<T> {
+    queue: Vec<T>,
+}
+
+impl<T> Events<T> {
+    pub fn new() -> Self {
+        Events { queue: Vec::new() }
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.queue.push(event);
+    }
+
+    pub fn drain(&mut self) -> std::vec::Drain<'_, T> {
+        self.queue.drain(..)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Events::new()
+    }
+}
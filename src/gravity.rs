@@ -0,0 +1,94 @@
+use glam::DVec2;
+
+use crate::barnes_hut::QuadTree;
+
+pub const G: f64 = physical_constants::NEWTONIAN_CONSTANT_OF_GRAVITATION;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointMass {
+    pub pos: DVec2,
+    pub mass: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GravitySolver {
+    //exact pairwise summation, O(n*m)
+    #[default]
+    Direct,
+    //quadtree approximation, O(m*log(n)); theta = 0 degenerates to exact summation
+    BarnesHut {
+        theta: f64,
+    },
+}
+
+impl GravitySolver {
+    pub const DEFAULT_THETA: f64 = 0.5;
+
+    //gravitational acceleration at every target produced by all sources,
+    //a source located exactly at the target is skipped (self interaction)
+    pub fn accelerations(
+        &self,
+        sources: &[PointMass],
+        targets: &[DVec2],
+        softening: f64,
+    ) -> Vec<DVec2> {
+        match *self {
+            GravitySolver::Direct => targets
+                .iter()
+                .map(|t| {
+                    sources.iter().fold(DVec2::ZERO, |acc, s| {
+                        acc + point_acceleration(*t, s.pos, s.mass, softening)
+                    })
+                })
+                .collect(),
+            GravitySolver::BarnesHut { theta } => {
+                let tree = QuadTree::new(sources);
+                targets
+                    .iter()
+                    .map(|t| tree.acceleration(*t, theta, softening))
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gravity {
+    pub solver: GravitySolver,
+    //Plummer softening length, keeps forces finite when bodies pass through each other
+    pub softening: f64,
+}
+
+impl Gravity {
+    pub const DEFAULT_SOFTENING: f64 = 1e4;
+
+    pub fn accelerations(&self, sources: &[PointMass], targets: &[DVec2]) -> Vec<DVec2> {
+        self.solver.accelerations(sources, targets, self.softening)
+    }
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Gravity {
+            solver: GravitySolver::default(),
+            softening: Gravity::DEFAULT_SOFTENING,
+        }
+    }
+}
+
+#[inline]
+pub fn point_acceleration(at: DVec2, source_pos: DVec2, source_mass: f64, softening: f64) -> DVec2 {
+    let r_vec = source_pos - at;
+    let r_sq = r_vec.length_squared();
+    if r_sq == 0.0 {
+        return DVec2::ZERO;
+    }
+    let soft_sq = r_sq + softening * softening;
+    r_vec * (G * source_mass / (soft_sq * soft_sq.sqrt()))
+}
+
+//potential energy of a pair, consistent with the softened force
+#[inline]
+pub fn potential_energy(m1: f64, m2: f64, distance: f64, softening: f64) -> f64 {
+    -G * m1 * m2 / (distance * distance + softening * softening).sqrt()
+}
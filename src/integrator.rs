@@ -0,0 +1,232 @@
+use glam::DVec2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct State {
+    pub pos: DVec2,
+    pub vel: DVec2,
+    //acceleration at the end of the previous step
+    pub acc: DVec2,
+}
+
+//advances all states by `dt`, `accelerations(t, positions)` evaluates accelerations
+//for the given positions at time offset `t` inside the step
+pub trait Integrator: Send + Sync + 'static {
+    fn step<F>(states: &mut [State], dt: f64, accelerations: F)
+    where
+        F: FnMut(f64, &[DVec2]) -> Vec<DVec2>;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum IntegratorKind {
+    #[default]
+    VelocityVerlet,
+    RungeKutta4,
+    Yoshida4,
+}
+
+//block timesteps: a body whose time scale is shorter than the step is advanced in
+//2^level substeps of its own, while the rest of the system moves with the full step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveStepping {
+    //fraction of the encounter time scale one substep may cover
+    pub eta: f64,
+    //0 disables substepping
+    pub max_level: u32,
+}
+
+impl AdaptiveStepping {
+    pub const DEFAULT_ETA: f64 = 0.05;
+    pub const DEFAULT_MAX_LEVEL: u32 = 8;
+    //1024 substeps of a single body per step is already far from real time
+    pub const LIMIT_MAX_LEVEL: u32 = 10;
+
+    pub fn disabled() -> Self {
+        AdaptiveStepping {
+            eta: Self::DEFAULT_ETA,
+            max_level: 0,
+        }
+    }
+
+    //smallest level whose substep fits into `time_scale`
+    pub fn level(&self, dt: f64, time_scale: f64) -> u32 {
+        let ratio = dt / (self.eta * time_scale);
+        if ratio.is_nan() || ratio <= 1.0 {
+            0
+        } else {
+            (ratio.log2().ceil() as u32).min(self.max_level)
+        }
+    }
+}
+
+impl Default for AdaptiveStepping {
+    fn default() -> Self {
+        AdaptiveStepping::disabled()
+    }
+}
+
+fn positions(states: &[State]) -> Vec<DVec2> {
+    states.iter().map(|s| s.pos).collect()
+}
+
+//2nd order symplectic, one force evaluation per step
+pub struct VelocityVerlet;
+
+impl Integrator for VelocityVerlet {
+    fn step<F>(states: &mut [State], dt: f64, mut accelerations: F)
+    where
+        F: FnMut(f64, &[DVec2]) -> Vec<DVec2>,
+    {
+        for s in states.iter_mut() {
+            s.pos += s.vel * dt + 0.5 * s.acc * dt * dt;
+        }
+        let new_acc = accelerations(dt, &positions(states));
+        for (s, a) in states.iter_mut().zip(new_acc) {
+            s.vel += 0.5 * (s.acc + a) * dt;
+            s.acc = a;
+        }
+    }
+}
+
+//classic 4th order Runge-Kutta, not symplectic, four force evaluations per step
+pub struct RungeKutta4;
+
+impl Integrator for RungeKutta4 {
+    fn step<F>(states: &mut [State], dt: f64, mut accelerations: F)
+    where
+        F: FnMut(f64, &[DVec2]) -> Vec<DVec2>,
+    {
+        let x0 = positions(states);
+        let v0: Vec<_> = states.iter().map(|s| s.vel).collect();
+        let shifted = |d: &[DVec2], h: f64| -> Vec<DVec2> {
+            x0.iter().zip(d).map(|(x, d)| *x + *d * h).collect()
+        };
+
+        let k1v = accelerations(0.0, &x0);
+        let k1x = v0.clone();
+        let k2x: Vec<_> = v0
+            .iter()
+            .zip(&k1v)
+            .map(|(v, a)| *v + *a * 0.5 * dt)
+            .collect();
+        let k2v = accelerations(0.5 * dt, &shifted(&k1x, 0.5 * dt));
+        let k3x: Vec<_> = v0
+            .iter()
+            .zip(&k2v)
+            .map(|(v, a)| *v + *a * 0.5 * dt)
+            .collect();
+        let k3v = accelerations(0.5 * dt, &shifted(&k2x, 0.5 * dt));
+        let k4x: Vec<_> = v0.iter().zip(&k3v).map(|(v, a)| *v + *a * dt).collect();
+        let k4v = accelerations(dt, &shifted(&k3x, dt));
+
+        for (i, s) in states.iter_mut().enumerate() {
+            s.pos += dt / 6.0 * (k1x[i] + 2.0 * k2x[i] + 2.0 * k3x[i] + k4x[i]);
+            s.vel += dt / 6.0 * (k1v[i] + 2.0 * k2v[i] + 2.0 * k3v[i] + k4v[i]);
+            s.acc = k4v[i];
+        }
+    }
+}
+
+//4th order symplectic composition of leapfrog steps (Yoshida 1990, Forest-Ruth)
+pub struct Yoshida4;
+
+impl Yoshida4 {
+    const W1: f64 = 1.351_207_191_959_657_8; // 1 / (2 - 2^(1/3))
+    const W0: f64 = -1.702_414_383_919_315_3; // -2^(1/3) / (2 - 2^(1/3))
+    const C: [f64; 4] = [
+        Self::W1 / 2.0,
+        (Self::W0 + Self::W1) / 2.0,
+        (Self::W0 + Self::W1) / 2.0,
+        Self::W1 / 2.0,
+    ];
+    const D: [f64; 3] = [Self::W1, Self::W0, Self::W1];
+}
+
+impl Integrator for Yoshida4 {
+    fn step<F>(states: &mut [State], dt: f64, mut accelerations: F)
+    where
+        F: FnMut(f64, &[DVec2]) -> Vec<DVec2>,
+    {
+        let mut t = 0.0;
+        for (c, d) in Self::C.iter().zip(Self::D.iter().map(Some).chain([None])) {
+            for s in states.iter_mut() {
+                s.pos += s.vel * c * dt;
+            }
+            t += c * dt;
+            if let Some(d) = d {
+                let acc = accelerations(t, &positions(states));
+                for (s, a) in states.iter_mut().zip(acc) {
+                    s.vel += a * d * dt;
+                    s.acc = a;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use super::*;
+
+    //unit circular orbit around mu = 1, the period is TAU
+    fn kepler(pos: DVec2) -> DVec2 {
+        -pos / pos.length().powi(3)
+    }
+
+    fn energy(s: &State) -> f64 {
+        0.5 * s.vel.length_squared() - 1.0 / s.pos.length()
+    }
+
+    //state after `orbits` periods with `steps` steps per period
+    fn orbit<I: Integrator>(orbits: usize, steps: usize) -> State {
+        let pos = DVec2::X;
+        let mut states = [State {
+            pos,
+            vel: DVec2::Y,
+            acc: kepler(pos),
+        }];
+        let dt = TAU / steps as f64;
+        for _ in 0..orbits * steps {
+            I::step(&mut states, dt, |_, positions| {
+                positions.iter().map(|p| kepler(*p)).collect()
+            });
+        }
+        states[0]
+    }
+
+    //after one period the body is back where it started
+    fn convergence_order<I: Integrator>() -> f64 {
+        let coarse = orbit::<I>(1, 100).pos.distance(DVec2::X);
+        let fine = orbit::<I>(1, 200).pos.distance(DVec2::X);
+        (coarse / fine).log2()
+    }
+
+    fn energy_drift<I: Integrator>() -> f64 {
+        ((energy(&orbit::<I>(20, 100)) + 0.5) / 0.5).abs()
+    }
+
+    #[test]
+    fn velocity_verlet_is_second_order() {
+        let order = convergence_order::<VelocityVerlet>();
+        assert!((1.8..=2.2).contains(&order), "order {}", order);
+        let drift = energy_drift::<VelocityVerlet>();
+        assert!(drift < 1e-3, "energy drift {}", drift);
+    }
+
+    #[test]
+    fn runge_kutta_is_fourth_order() {
+        let order = convergence_order::<RungeKutta4>();
+        assert!((3.7..=4.3).contains(&order), "order {}", order);
+        let drift = energy_drift::<RungeKutta4>();
+        assert!(drift < 1e-5, "energy drift {}", drift);
+    }
+
+    #[test]
+    fn yoshida_is_fourth_order() {
+        let order = convergence_order::<Yoshida4>();
+        assert!((3.7..=4.3).contains(&order), "order {}", order);
+        let drift = energy_drift::<Yoshida4>();
+        assert!(drift < 1e-5, "energy drift {}", drift);
+    }
+}
@@ -1,5 +0,0 @@
-use uom::si::{f64::Length, f64::Velocity};
-pub struct FlightInfo {
-    pub delta: Velocity,
-    pub distance: Length
-}
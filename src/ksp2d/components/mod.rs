@@ -1,6 +0,0 @@
-pub mod celestial_body;
-pub mod closest_celestial_body;
-pub mod landing;
-pub mod newton_body;
-pub mod rocket;
-pub mod flight_info;
@@ -1,3 +0,0 @@
-pub mod collision;
-pub mod components;
-pub mod systems;
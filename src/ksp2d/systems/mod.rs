@@ -1,9 +0,0 @@
-pub mod celestial_body;
-pub mod closest_celestial;
-pub mod landing;
-pub mod newton_body;
-pub mod performance_info;
-pub mod planet_resting;
-pub mod render;
-pub mod rocket;
-pub mod flight_info;
@@ -0,0 +1,47 @@
+pub mod assembly;
+pub mod barnes_hut;
+pub mod collision;
+pub mod color;
+pub mod components;
+pub mod events;
+#[cfg(feature = "render")]
+pub mod fonts;
+pub mod gravity;
+pub mod integrator;
+pub mod orbit;
+pub mod physics_clock;
+pub mod settings;
+pub mod simulation;
+pub mod system_generation;
+pub mod systems;
+
+use std::time::{Duration, Instant};
+
+use glam::IVec2;
+#[cfg(feature = "render")]
+use sdl2::{
+    render::{Canvas, TextureCreator},
+    video::{Window, WindowContext},
+};
+
+//length of one physics step in seconds
+pub struct Dt(pub f64);
+pub struct FrameDt(pub f64);
+pub struct FrameTimer(pub Instant);
+pub struct FrameDuration(pub Duration);
+
+pub struct WindowSize(pub IVec2);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraMode {
+    Default,
+    Landing,
+}
+
+pub const SPACE_SIZE: f64 = 1e10;
+
+#[cfg(feature = "render")]
+pub struct CanvasResources {
+    pub canvas: Canvas<Window>,
+    pub texture_creator: TextureCreator<WindowContext>,
+}
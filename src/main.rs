@@ -1,68 +1,43 @@
-pub mod fonts;
-pub mod ksp2d;
-pub mod system_generation;
-
-extern crate crossbeam;
 extern crate glam;
 extern crate legion;
-extern crate rand;
 extern crate sdl2;
 
-use fonts::{load_fonts, FontRenderer};
-use glam::{dvec2, ivec2, DVec2, I16Vec2, IVec2};
-use ksp2d::components::closest_celestial_body::ClosestCelestialBody;
-use ksp2d::components::newton_body::NewtonBody;
-use ksp2d::components::rocket::Rocket;
-use ksp2d::systems::closest_celestial::closest_celestial_system;
-use ksp2d::systems::landing::landing_system;
-use ksp2d::systems::newton_body::celestial_body_system;
+use glam::{ivec2, I16Vec2};
+use ksp2d::assembly::load_rocket;
+use ksp2d::components::landing::Crashed;
+use ksp2d::components::rocket::PlayerInput;
+use ksp2d::components::sas::SasMode;
+use ksp2d::fonts::{load_fonts, FontRenderer};
+use ksp2d::physics_clock::PhysicsClock;
+use ksp2d::settings::{Settings, USAGE};
+use ksp2d::simulation::Simulation;
+use ksp2d::systems::conservation::ConservationInfo;
+use ksp2d::systems::flight_info::flight_info_system;
+use ksp2d::systems::landing::log_touchdowns_system;
+use ksp2d::systems::maneuver::maneuver_system;
 use ksp2d::systems::performance_info::{update_info_system, PerformanceInfo};
-use ksp2d::systems::planet_resting::planet_resting_system;
-use ksp2d::systems::render::render_system;
-use ksp2d::systems::rocket::update_positions_system;
+use ksp2d::systems::render::{render_hud_system, render_system};
+use ksp2d::systems::sphere_of_influence::log_soi_changes_system;
+use ksp2d::systems::time_warp::{time_warp_limit_system, TimeWarp};
+use ksp2d::systems::trajectory::trajectory_system;
+use ksp2d::{
+    CameraMode, CanvasResources, FrameDt, FrameDuration, FrameTimer, WindowSize, SPACE_SIZE,
+};
 use sdl2::event::WindowEvent;
 use sdl2::mixer::InitFlag;
-use sdl2::render::{Canvas, TextureCreator, WindowCanvas};
-use sdl2::video::{Window, WindowContext};
+use sdl2::render::WindowCanvas;
 use sdl2::EventPump;
 use sdl2::{event::Event, keyboard::Scancode};
 use std::collections::HashSet;
 use std::time::{Duration, Instant};
-use system_generation::get_system;
-use systems::CommandBuffer;
-use uom::si::f64::{Length, Velocity};
-use uom::si::length::meter;
-use uom::si::velocity::meter_per_second;
 
 use std::cmp::Ordering;
 
 use legion::*;
 
-use crate::ksp2d::components::flight_info::FlightInfo;
-use crate::ksp2d::components::rocket::PlayerInput;
-use crate::ksp2d::systems::flight_info::flight_info_system;
-
-pub struct Dt(f64);
-pub struct FrameTimer(Instant);
-pub struct FrameDuration(Duration);
-
-pub struct WindowSize(IVec2);
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum CameraMode {
-    Default,
-    Landing,
-}
-
-const SPACE_SIZE: f64 = 1e10;
 const INITIAL_WINDOW_WIDTH: u32 = 1280;
 const INITIAL_WINDOW_HEIGHT: u32 = 720;
 
-pub struct CanvasResources {
-    pub canvas: Canvas<Window>,
-    pub texture_creator: TextureCreator<WindowContext>,
-}
-
 fn initialize() -> Result<(WindowCanvas, EventPump), String> {
     let sdl_context = sdl2::init()?;
     let video_subsystem = sdl_context.video()?;
@@ -90,8 +65,8 @@ fn initialize() -> Result<(WindowCanvas, EventPump), String> {
     Ok((canvas, event_pump))
 }
 
-fn initial_resources(canvas: Canvas<Window>, world: &World) -> Resources {
-    let mut resources = Resources::default();
+//window, fonts and frame timing on top of the simulation resources
+fn add_render_resources(resources: &mut Resources, canvas: WindowCanvas) {
     let texture_creator = canvas.texture_creator();
 
     let canvas_resources = CanvasResources {
@@ -108,77 +83,79 @@ fn initial_resources(canvas: Canvas<Window>, world: &World) -> Resources {
     resources.insert(canvas_resources);
     resources.insert(perf_info);
     resources.insert(font_renderer);
-    resources.insert(HashSet::<PlayerInput>::new());
 
     let initial_size = ivec2(INITIAL_WINDOW_WIDTH as i32, INITIAL_WINDOW_HEIGHT as i32);
 
     resources.insert(WindowSize(initial_size));
     resources.insert(FrameTimer(Instant::now()));
     resources.insert(FrameDuration(Duration::ZERO));
-    resources.insert(Dt(0.0));
+    resources.insert(FrameDt(0.0));
     resources.insert(CameraMode::Default);
-
-    let command_buffer = CommandBuffer::new(world);
-    resources.insert(command_buffer);
-    resources
-}
-
-fn initial_world() -> World {
-    let mut world = World::default();
-    let rocket_body = NewtonBody {
-        angle: DVec2::Y,
-        angular_vel: 0.0,
-        mass: 2965000.0,
-        pos: dvec2(SPACE_SIZE / 8.0, SPACE_SIZE / 8.0),
-        vel: DVec2::ZERO,
-        acc: DVec2::ZERO,
-    };
-
-    let sys = get_system(SPACE_SIZE * 0.5);
-    let first_celestial = world.extend(sys);
-    let first_celestial_enity = *first_celestial.first().unwrap();
-    world.push((
-        Rocket::new(),
-        rocket_body,
-        ClosestCelestialBody {
-            id: first_celestial_enity,
-            closest_surface_point: DVec2::ZERO,
-            closest_surface_point_a: DVec2::ZERO,
-        },
-        FlightInfo {
-            delta: Velocity::new::<meter_per_second>(0.0),
-            distance: Length::new::<meter>(0.0),
-        },
-    ));
-    world
 }
 
 pub fn main() {
     std::env::set_var("RUST_LOG", "info");
     std::env::set_var("RUST_BACKTRACE", "1");
     env_logger::init();
+    let settings = match Settings::from_args(std::env::args().skip(1)) {
+        Ok(settings) => settings,
+        Err(e) => {
+            log::error!("{}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    log::info!(
+        "seed: {}, gravity: {:?}, integrator: {:?}, physics step: {} s, max substeps: {}, on rails: {}, adaptive stepping: {:?}, collisions: {:?}, landing: {:?}",
+        settings.seed,
+        settings.gravity,
+        settings.integrator,
+        settings.physics_step,
+        settings.max_substeps,
+        settings.on_rails,
+        settings.adaptive_stepping,
+        settings.collision_outcome,
+        settings.landing
+    );
+    let rocket = match load_rocket(&settings.rocket) {
+        Ok(rocket) => rocket,
+        Err(e) => {
+            log::error!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    log::info!(
+        "rocket: {}, {} stages, {:.0} kg",
+        settings.rocket,
+        rocket.stages.len(),
+        rocket.mass()
+    );
     let (canvas, mut event_pump) = initialize().unwrap();
-    let mut world = initial_world();
-    let mut resources = initial_resources(canvas, &world);
-    let mut schedule = Schedule::builder()
-        .add_system(update_positions_system())
-        .add_system(celestial_body_system())
+    let mut simulation = Simulation::new(&settings, rocket);
+    add_render_resources(&mut simulation.resources, canvas);
+    let mut frame_schedule = Schedule::builder()
         .add_system(update_info_system())
-        .add_system(landing_system())
-        .add_system(planet_resting_system())
-        .add_system(closest_celestial_system())
         .add_system(flight_info_system())
+        .add_system(time_warp_limit_system())
+        .add_system(log_soi_changes_system())
+        .add_system(log_touchdowns_system())
+        .add_system(trajectory_system())
+        .add_system(maneuver_system())
         .flush()
         .add_thread_local(render_system())
+        .add_thread_local(render_hud_system())
         .build();
 
     'running: loop {
+        let mut revert = false;
         {
-            let mut frame_timer = resources.get_mut::<FrameTimer>().unwrap();
-            let mut frame_duration = resources.get_mut::<Dt>().unwrap();
-            frame_duration.0 = frame_timer.0.elapsed().as_secs_f64();
+            let mut frame_timer = simulation.resources.get_mut::<FrameTimer>().unwrap();
+            let mut frame_dt = simulation.resources.get_mut::<FrameDt>().unwrap();
+            frame_dt.0 = frame_timer.0.elapsed().as_secs_f64();
             frame_timer.0 = Instant::now();
-            let mut pinput = resources.get_mut::<HashSet<PlayerInput>>().unwrap();
+            let mut pinput = simulation
+                .resources
+                .get_mut::<HashSet<PlayerInput>>()
+                .unwrap();
             for event in event_pump.poll_iter() {
                 match event {
                     Event::Quit { .. }
@@ -190,7 +167,8 @@ pub fn main() {
                         scancode: Some(Scancode::C),
                         ..
                     } => {
-                        let mut camera_mode_res = resources.get_mut::<CameraMode>().unwrap();
+                        let mut camera_mode_res =
+                            simulation.resources.get_mut::<CameraMode>().unwrap();
                         let new_mode = if *camera_mode_res == CameraMode::Default {
                             CameraMode::Landing
                         } else {
@@ -198,6 +176,52 @@ pub fn main() {
                         };
                         *camera_mode_res = new_mode;
                     }
+                    Event::KeyDown {
+                        scancode: Some(Scancode::R),
+                        repeat: false,
+                        ..
+                    } => revert = true,
+                    Event::KeyDown {
+                        scancode: Some(Scancode::F3),
+                        ..
+                    } => {
+                        let mut conservation =
+                            simulation.resources.get_mut::<ConservationInfo>().unwrap();
+                        conservation.visible = !conservation.visible;
+                    }
+                    Event::KeyDown {
+                        scancode:
+                            Some(code @ (Scancode::Period | Scancode::Comma | Scancode::Slash)),
+                        ..
+                    } => {
+                        let mut time_warp = simulation.resources.get_mut::<TimeWarp>().unwrap();
+                        match code {
+                            Scancode::Period => time_warp.increase(),
+                            Scancode::Comma => time_warp.decrease(),
+                            _ => time_warp.reset(),
+                        }
+                    }
+                    Event::KeyDown {
+                        scancode: Some(Scancode::Space),
+                        repeat: false,
+                        ..
+                    } => {
+                        pinput.insert(PlayerInput::Stage);
+                    }
+                    Event::KeyDown {
+                        scancode: Some(code),
+                        repeat: false,
+                        ..
+                    } if sas_input(code).is_some() => {
+                        pinput.extend(sas_input(code));
+                    }
+                    Event::KeyDown {
+                        scancode: Some(Scancode::N),
+                        repeat: false,
+                        ..
+                    } => {
+                        pinput.insert(PlayerInput::ToggleManeuver);
+                    }
                     Event::KeyDown {
                         scancode: Some(code),
                         ..
@@ -210,6 +234,14 @@ pub fn main() {
                             Scancode::Q => Some(PlayerInput::RotateLeft),
                             Scancode::E => Some(PlayerInput::RotateRight),
                             Scancode::C => Some(PlayerInput::SwitchCamera),
+                            Scancode::I => Some(PlayerInput::ManeuverPrograde),
+                            Scancode::K => Some(PlayerInput::ManeuverRetrograde),
+                            Scancode::H => Some(PlayerInput::ManeuverNormal),
+                            Scancode::Y => Some(PlayerInput::ManeuverAntiNormal),
+                            Scancode::L => Some(PlayerInput::ManeuverRadialOut),
+                            Scancode::J => Some(PlayerInput::ManeuverRadialIn),
+                            Scancode::U => Some(PlayerInput::ManeuverEarlier),
+                            Scancode::O => Some(PlayerInput::ManeuverLater),
                             _ => None,
                         };
                         if let Some(player_input) = insertion {
@@ -228,6 +260,14 @@ pub fn main() {
                             Scancode::Q => Some(&PlayerInput::RotateLeft),
                             Scancode::E => Some(&PlayerInput::RotateRight),
                             Scancode::C => Some(&PlayerInput::SwitchCamera),
+                            Scancode::I => Some(&PlayerInput::ManeuverPrograde),
+                            Scancode::K => Some(&PlayerInput::ManeuverRetrograde),
+                            Scancode::H => Some(&PlayerInput::ManeuverNormal),
+                            Scancode::Y => Some(&PlayerInput::ManeuverAntiNormal),
+                            Scancode::L => Some(&PlayerInput::ManeuverRadialOut),
+                            Scancode::J => Some(&PlayerInput::ManeuverRadialIn),
+                            Scancode::U => Some(&PlayerInput::ManeuverEarlier),
+                            Scancode::O => Some(&PlayerInput::ManeuverLater),
                             _ => None,
                         };
                         if let Some(player_input) = insertion {
@@ -238,18 +278,50 @@ pub fn main() {
                         win_event: WindowEvent::Resized(x, y),
                         ..
                     } => {
-                        let mut window_size = resources.get_mut::<WindowSize>().unwrap();
+                        let mut window_size = simulation.resources.get_mut::<WindowSize>().unwrap();
                         window_size.0 = ivec2(x, y);
                     }
                     _ => {}
                 }
             }
         }
+        //only offered once the rocket is wrecked
+        if revert && <&Crashed>::query().iter(&simulation.world).next().is_some() {
+            simulation.revert();
+        }
 
-        schedule.execute(&mut world, &mut resources);
+        let steps = {
+            let frame_dt = simulation.resources.get::<FrameDt>().unwrap().0;
+            let warp = simulation.resources.get::<TimeWarp>().unwrap().factor();
+            let mut clock = simulation.resources.get_mut::<PhysicsClock>().unwrap();
+            clock.set_warp(warp);
+            clock.advance(frame_dt)
+        };
+        for _ in 0..steps {
+            simulation.step();
+        }
+        frame_schedule.execute(&mut simulation.world, &mut simulation.resources);
     }
 }
 
+//T switches the stability assist, the number row picks its mode, Tab cycles targets
+fn sas_input(code: Scancode) -> Option<PlayerInput> {
+    let mode = match code {
+        Scancode::T => return Some(PlayerInput::ToggleSas),
+        Scancode::Tab => return Some(PlayerInput::CycleTarget),
+        Scancode::Num1 => SasMode::KillRotation,
+        Scancode::Num2 => SasMode::HoldHeading,
+        Scancode::Num3 => SasMode::Prograde,
+        Scancode::Num4 => SasMode::Retrograde,
+        Scancode::Num5 => SasMode::RadialOut,
+        Scancode::Num6 => SasMode::RadialIn,
+        Scancode::Num7 => SasMode::Target,
+        Scancode::Num8 => SasMode::Maneuver,
+        _ => return None,
+    };
+    Some(PlayerInput::SelectSas(mode))
+}
+
 fn get_scaling(x: i32, y: i32) -> (f64, I16Vec2) {
     let x_f = x as f64;
     let y_f = y as f64;
@@ -0,0 +1,467 @@
+use std::f64::consts::{PI, TAU};
+
+use glam::DVec2;
+
+const KEPLER_TOLERANCE: f64 = 1e-14;
+const KEPLER_MAX_ITERATIONS: usize = 50;
+//below this eccentricity the orbit is treated as circular, periapsis is put on +X
+const CIRCULAR_EPS: f64 = 1e-12;
+//eccentricities this close to 1 are treated as parabolic
+const PARABOLIC_EPS: f64 = 1e-9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Conic {
+    Elliptic,
+    Parabolic,
+    Hyperbolic,
+}
+
+impl Conic {
+    pub fn from_eccentricity(eccentricity: f64) -> Self {
+        if (eccentricity - 1.0).abs() < PARABOLIC_EPS {
+            Conic::Parabolic
+        } else if eccentricity < 1.0 {
+            Conic::Elliptic
+        } else {
+            Conic::Hyperbolic
+        }
+    }
+}
+
+//Keplerian elements of an orbit in the plane, relative to the parent body
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitalElements {
+    //gravitational parameter of the parent, G * M
+    pub mu: f64,
+    //negative for hyperbolic orbits, infinite for parabolic ones
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    //h^2 / mu, finite for every conic
+    pub semi_latus_rectum: f64,
+    //angle of periapsis measured from +X
+    pub argument_of_periapsis: f64,
+    //1.0 counter clockwise, -1.0 clockwise
+    pub direction: f64,
+    //elliptic, hyperbolic or parabolic (Barker) mean anomaly, see `mean_motion`
+    pub mean_anomaly_at_epoch: f64,
+    pub epoch: f64,
+}
+
+impl OrbitalElements {
+    //relative position and velocity of the orbiting body at time `epoch`
+    pub fn from_state_vectors(pos: DVec2, vel: DVec2, mu: f64, epoch: f64) -> Self {
+        let r = pos.length();
+        let v_sq = vel.length_squared();
+        let h = pos.perp_dot(vel);
+        let direction = if h < 0.0 { -1.0 } else { 1.0 };
+        let e_vec = ((v_sq - mu / r) * pos - pos.dot(vel) * vel) / mu;
+        let eccentricity = e_vec.length();
+        let semi_latus_rectum = h * h / mu;
+        let semi_major_axis = match Conic::from_eccentricity(eccentricity) {
+            Conic::Parabolic => f64::INFINITY,
+            _ => semi_latus_rectum / (1.0 - eccentricity * eccentricity),
+        };
+        let periapsis_dir = if eccentricity < CIRCULAR_EPS {
+            DVec2::X
+        } else {
+            e_vec / eccentricity
+        };
+        let mut elements = OrbitalElements {
+            mu,
+            semi_major_axis,
+            eccentricity,
+            semi_latus_rectum,
+            argument_of_periapsis: periapsis_dir.to_angle(),
+            direction,
+            mean_anomaly_at_epoch: 0.0,
+            epoch,
+        };
+        let true_anomaly = direction * periapsis_dir.angle_to(pos);
+        elements.mean_anomaly_at_epoch = elements
+            .mean_anomaly_from_eccentric(elements.eccentric_anomaly_from_true(true_anomaly));
+        elements
+    }
+
+    pub fn conic(&self) -> Conic {
+        Conic::from_eccentricity(self.eccentricity)
+    }
+
+    //rate of the mean anomaly, for parabolic orbits the Barker anomaly
+    //D + D^3 / 3 grows at this rate
+    pub fn mean_motion(&self) -> f64 {
+        match self.conic() {
+            Conic::Parabolic => 2.0 * (self.mu / self.semi_latus_rectum.powi(3)).sqrt(),
+            _ => (self.mu / self.semi_major_axis.abs().powi(3)).sqrt(),
+        }
+    }
+
+    //None for open orbits
+    pub fn period(&self) -> Option<f64> {
+        match self.conic() {
+            Conic::Elliptic => Some(TAU / self.mean_motion()),
+            _ => None,
+        }
+    }
+
+    pub fn periapsis(&self) -> f64 {
+        self.semi_latus_rectum / (1.0 + self.eccentricity)
+    }
+
+    //None for open orbits
+    pub fn apoapsis(&self) -> Option<f64> {
+        match self.conic() {
+            Conic::Elliptic => Some(self.semi_latus_rectum / (1.0 - self.eccentricity)),
+            _ => None,
+        }
+    }
+
+    //orbital energy per unit mass, negative for bound orbits
+    pub fn specific_energy(&self) -> f64 {
+        match self.conic() {
+            Conic::Parabolic => 0.0,
+            _ => -self.mu / (2.0 * self.semi_major_axis),
+        }
+    }
+
+    pub fn specific_angular_momentum(&self) -> f64 {
+        self.direction * (self.mu * self.semi_latus_rectum).sqrt()
+    }
+
+    pub fn mean_anomaly_at(&self, time: f64) -> f64 {
+        self.mean_anomaly_at_epoch + self.mean_motion() * (time - self.epoch)
+    }
+
+    //negative before the periapsis pass, wraps every period on elliptic orbits
+    pub fn time_since_periapsis(&self, time: f64) -> f64 {
+        let mean_anomaly = match self.conic() {
+            Conic::Elliptic => (self.mean_anomaly_at(time) + PI).rem_euclid(TAU) - PI,
+            _ => self.mean_anomaly_at(time),
+        };
+        mean_anomaly / self.mean_motion()
+    }
+
+    //time until the body next passes `true_anomaly` after `time`, None if it never will
+    pub fn time_to_true_anomaly(&self, time: f64, true_anomaly: f64) -> Option<f64> {
+        let mean_anomaly = self
+            .mean_anomaly_from_eccentric(self.eccentric_anomaly_from_true(true_anomaly))
+            - self.mean_anomaly_at(time);
+        match self.conic() {
+            Conic::Elliptic => Some(mean_anomaly.rem_euclid(TAU) / self.mean_motion()),
+            _ if true_anomaly.abs() >= self.max_true_anomaly() || mean_anomaly < 0.0 => None,
+            _ => Some(mean_anomaly / self.mean_motion()),
+        }
+    }
+
+    //asymptote of open orbits, PI for closed ones
+    pub fn max_true_anomaly(&self) -> f64 {
+        match self.conic() {
+            Conic::Elliptic => PI,
+            _ => (-1.0 / self.eccentricity).acos(),
+        }
+    }
+
+    //eccentric anomaly E, hyperbolic anomaly H or parabolic anomaly D = tan(v/2)
+    pub fn eccentric_anomaly_at(&self, time: f64) -> f64 {
+        let mean_anomaly = self.mean_anomaly_at(time);
+        match self.conic() {
+            Conic::Elliptic => solve_kepler(mean_anomaly, self.eccentricity),
+            Conic::Parabolic => solve_barker(mean_anomaly),
+            Conic::Hyperbolic => solve_kepler_hyperbolic(mean_anomaly, self.eccentricity),
+        }
+    }
+
+    pub fn true_anomaly_at(&self, time: f64) -> f64 {
+        self.true_anomaly_from_eccentric(self.eccentric_anomaly_at(time))
+    }
+
+    pub fn eccentric_anomaly_from_true(&self, true_anomaly: f64) -> f64 {
+        let e = self.eccentricity;
+        let half_tan = (0.5 * true_anomaly).tan();
+        match self.conic() {
+            Conic::Elliptic => 2.0 * (((1.0 - e) / (1.0 + e)).sqrt() * half_tan).atan(),
+            Conic::Parabolic => half_tan,
+            Conic::Hyperbolic => 2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * half_tan).atanh(),
+        }
+    }
+
+    pub fn true_anomaly_from_eccentric(&self, eccentric_anomaly: f64) -> f64 {
+        let e = self.eccentricity;
+        match self.conic() {
+            Conic::Elliptic => {
+                2.0 * ((1.0 + e).sqrt() * (0.5 * eccentric_anomaly).sin())
+                    .atan2((1.0 - e).sqrt() * (0.5 * eccentric_anomaly).cos())
+            }
+            Conic::Parabolic => 2.0 * eccentric_anomaly.atan(),
+            Conic::Hyperbolic => {
+                2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (0.5 * eccentric_anomaly).tanh()).atan()
+            }
+        }
+    }
+
+    pub fn mean_anomaly_from_eccentric(&self, eccentric_anomaly: f64) -> f64 {
+        let e = self.eccentricity;
+        match self.conic() {
+            Conic::Elliptic => eccentric_anomaly - e * eccentric_anomaly.sin(),
+            Conic::Parabolic => eccentric_anomaly + eccentric_anomaly.powi(3) / 3.0,
+            Conic::Hyperbolic => e * eccentric_anomaly.sinh() - eccentric_anomaly,
+        }
+    }
+
+    //relative position and velocity of the orbiting body at `time`
+    pub fn state_vectors_at(&self, time: f64) -> (DVec2, DVec2) {
+        self.state_vectors_at_true_anomaly(self.true_anomaly_at(time))
+    }
+
+    pub fn state_vectors_at_true_anomaly(&self, true_anomaly: f64) -> (DVec2, DVec2) {
+        let e = self.eccentricity;
+        let p = self.semi_latus_rectum;
+        let (sin, cos) = true_anomaly.sin_cos();
+        let r = p / (1.0 + e * cos);
+        let periapsis = DVec2::from_angle(self.argument_of_periapsis);
+        let pos = DVec2::new(r * cos, self.direction * r * sin);
+        let vel = (self.mu / p).sqrt() * DVec2::new(-sin, self.direction * (e + cos));
+        (periapsis.rotate(pos), periapsis.rotate(vel))
+    }
+}
+
+//eccentric anomaly for the given mean anomaly, Newton iteration on E - e*sin(E) = M
+pub fn solve_kepler(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let m = (mean_anomaly + PI).rem_euclid(TAU) - PI;
+    let mut e_anomaly = if eccentricity < 0.8 {
+        m + eccentricity * m.sin()
+    } else {
+        PI.copysign(m)
+    };
+    for _ in 0..KEPLER_MAX_ITERATIONS {
+        let delta = (e_anomaly - eccentricity * e_anomaly.sin() - m)
+            / (1.0 - eccentricity * e_anomaly.cos());
+        e_anomaly -= delta;
+        if delta.abs() < KEPLER_TOLERANCE {
+            break;
+        }
+    }
+    e_anomaly
+}
+
+//hyperbolic anomaly, Newton iteration on e*sinh(H) - H = M
+pub fn solve_kepler_hyperbolic(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let m = mean_anomaly;
+    let mut h_anomaly = (2.0 * m.abs() / eccentricity + 1.8).ln().copysign(m);
+    for _ in 0..KEPLER_MAX_ITERATIONS {
+        let delta = (eccentricity * h_anomaly.sinh() - h_anomaly - m)
+            / (eccentricity * h_anomaly.cosh() - 1.0);
+        h_anomaly -= delta;
+        if delta.abs() < KEPLER_TOLERANCE * h_anomaly.abs().max(1.0) {
+            break;
+        }
+    }
+    h_anomaly
+}
+
+//parabolic anomaly D for Barker's equation D + D^3 / 3 = M, solved in closed form
+pub fn solve_barker(mean_anomaly: f64) -> f64 {
+    let m = mean_anomaly.abs();
+    let y = (1.5 * m + (2.25 * m * m + 1.0).sqrt()).cbrt();
+    (y - 1.0 / y).copysign(mean_anomaly)
+}
+
+//Laplace sphere of influence of a body of `mass` orbiting `parent_mass` at `distance`
+pub fn soi_radius(distance: f64, mass: f64, parent_mass: f64) -> f64 {
+    distance * (mass / parent_mass).powf(0.4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EARTH_MU: f64 = 3.986_004_418e14;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance * expected.abs().max(1.0),
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    fn assert_vec_close(actual: DVec2, expected: DVec2, tolerance: f64) {
+        assert!(
+            actual.distance(expected) <= tolerance * expected.length().max(1.0),
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    //body at periapsis on +X moving counter clockwise
+    fn at_periapsis(periapsis: f64, speed: f64) -> OrbitalElements {
+        OrbitalElements::from_state_vectors(
+            DVec2::new(periapsis, 0.0),
+            DVec2::new(0.0, speed),
+            EARTH_MU,
+            0.0,
+        )
+    }
+
+    #[test]
+    fn circular_low_orbit() {
+        let r = 6_778_000.0;
+        let orbit = at_periapsis(r, (EARTH_MU / r).sqrt());
+        assert_eq!(orbit.conic(), Conic::Elliptic);
+        assert_close(orbit.semi_major_axis, r, 1e-12);
+        assert!(orbit.eccentricity < 1e-12);
+        assert_close(orbit.period().unwrap(), 5553.6, 1e-4);
+        assert_close(orbit.specific_energy(), -EARTH_MU / (2.0 * r), 1e-12);
+        assert_close(orbit.apoapsis().unwrap(), r, 1e-12);
+    }
+
+    #[test]
+    fn elliptic_orbit() {
+        let (periapsis, apoapsis) = (7_000_000.0, 13_000_000.0);
+        let a = 0.5 * (periapsis + apoapsis);
+        let orbit = at_periapsis(periapsis, (EARTH_MU * (2.0 / periapsis - 1.0 / a)).sqrt());
+        assert_eq!(orbit.conic(), Conic::Elliptic);
+        assert_close(orbit.semi_major_axis, a, 1e-12);
+        assert_close(orbit.eccentricity, 0.3, 1e-12);
+        assert_close(orbit.periapsis(), periapsis, 1e-12);
+        assert_close(orbit.apoapsis().unwrap(), apoapsis, 1e-12);
+        assert_close(orbit.argument_of_periapsis, 0.0, 1e-12);
+        assert_close(orbit.mean_anomaly_at_epoch, 0.0, 1e-12);
+
+        //half a period later the body is at apoapsis on -X
+        let period = orbit.period().unwrap();
+        let (pos, vel) = orbit.state_vectors_at(0.5 * period);
+        assert_vec_close(pos, DVec2::new(-apoapsis, 0.0), 1e-9);
+        assert_close(vel.x, 0.0, 1e-9);
+        assert!(vel.y < 0.0);
+        assert_close(orbit.true_anomaly_at(0.5 * period).abs(), PI, 1e-9);
+    }
+
+    #[test]
+    fn hyperbolic_orbit() {
+        let periapsis = 7_000_000.0;
+        let escape = (2.0 * EARTH_MU / periapsis).sqrt();
+        let orbit = at_periapsis(periapsis, escape * 1.2);
+        assert_eq!(orbit.conic(), Conic::Hyperbolic);
+        assert!(orbit.semi_major_axis < 0.0);
+        assert_close(orbit.eccentricity, 1.2 * 1.2 * 2.0 - 1.0, 1e-12);
+        assert_close(orbit.periapsis(), periapsis, 1e-12);
+        assert!(orbit.period().is_none());
+        assert!(orbit.apoapsis().is_none());
+        assert!(orbit.specific_energy() > 0.0);
+    }
+
+    #[test]
+    fn parabolic_orbit() {
+        let periapsis = 7_000_000.0;
+        let orbit = at_periapsis(periapsis, (2.0 * EARTH_MU / periapsis).sqrt());
+        assert_eq!(orbit.conic(), Conic::Parabolic);
+        assert!(orbit.semi_major_axis.is_infinite());
+        assert_close(orbit.periapsis(), periapsis, 1e-12);
+        assert_eq!(orbit.specific_energy(), 0.0);
+
+        //energy stays zero along the trajectory
+        let (pos, vel) = orbit.state_vectors_at(3600.0);
+        assert_close(0.5 * vel.length_squared(), EARTH_MU / pos.length(), 1e-9);
+    }
+
+    #[test]
+    fn state_vectors_round_trip() {
+        let cases = [
+            (DVec2::new(7e6, -2e6), DVec2::new(1500.0, 7200.0)),
+            (DVec2::new(-3e6, 9e6), DVec2::new(-6800.0, -1200.0)),
+            (DVec2::new(7e6, 1e6), DVec2::new(-2000.0, -12000.0)),
+            (
+                DVec2::new(2e7, 0.0),
+                DVec2::new(0.0, (2.0 * EARTH_MU / 2e7).sqrt()),
+            ),
+        ];
+        for (pos, vel) in cases.iter() {
+            let orbit = OrbitalElements::from_state_vectors(*pos, *vel, EARTH_MU, 100.0);
+            let (p, v) = orbit.state_vectors_at(100.0);
+            assert_vec_close(p, *pos, 1e-9);
+            assert_vec_close(v, *vel, 1e-9);
+
+            //elements taken later on the same orbit describe the same motion
+            let (later_pos, later_vel) = orbit.state_vectors_at(1000.0);
+            let later = OrbitalElements::from_state_vectors(later_pos, later_vel, EARTH_MU, 1000.0);
+            assert_eq!(later.conic(), orbit.conic());
+            assert_close(later.eccentricity, orbit.eccentricity, 1e-9);
+            assert_close(later.specific_energy(), orbit.specific_energy(), 1e-9);
+            let (p, v) = later.state_vectors_at(100.0);
+            assert_vec_close(p, *pos, 1e-8);
+            assert_vec_close(v, *vel, 1e-8);
+        }
+    }
+
+    #[test]
+    fn clockwise_orbit() {
+        let r = 7e6;
+        let orbit = OrbitalElements::from_state_vectors(
+            DVec2::new(r, 0.0),
+            DVec2::new(0.0, -(EARTH_MU / r).sqrt() * 1.1),
+            EARTH_MU,
+            0.0,
+        );
+        assert_eq!(orbit.direction, -1.0);
+        assert!(orbit.specific_angular_momentum() < 0.0);
+        let (pos, _) = orbit.state_vectors_at(60.0);
+        assert!(pos.y < 0.0);
+    }
+
+    #[test]
+    fn time_since_periapsis() {
+        let periapsis = 7_000_000.0;
+        let orbit = at_periapsis(periapsis, 9000.0);
+        let period = orbit.period().unwrap();
+        assert_close(
+            orbit.time_since_periapsis(0.25 * period),
+            0.25 * period,
+            1e-12,
+        );
+        assert_close(
+            orbit.time_since_periapsis(0.75 * period),
+            -0.25 * period,
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn time_to_true_anomaly() {
+        let periapsis = 7_000_000.0;
+        let orbit = at_periapsis(periapsis, 9000.0);
+        let period = orbit.period().unwrap();
+        let half = orbit.time_to_true_anomaly(0.0, PI).unwrap();
+        assert_close(half, 0.5 * period, 1e-12);
+        //periapsis was just passed, the next pass is a period away
+        let next = orbit.time_to_true_anomaly(1.0, 0.0).unwrap();
+        assert_close(next, period - 1.0, 1e-12);
+
+        let escape = at_periapsis(periapsis, 12000.0);
+        let ahead = escape.time_to_true_anomaly(0.0, 1.0).unwrap();
+        assert_close(escape.true_anomaly_at(ahead), 1.0, 1e-9);
+        assert!(escape.time_to_true_anomaly(100.0, 0.0).is_none());
+        assert!(escape.time_to_true_anomaly(0.0, 3.0).is_none());
+    }
+
+    #[test]
+    fn kepler_solvers() {
+        for e in [0.0, 0.3, 0.9, 0.99] {
+            for m in [-3.0, -0.5, 0.0, 0.1, 2.5] {
+                let ea = solve_kepler(m, e);
+                assert_close(ea - e * ea.sin(), m, 1e-12);
+            }
+        }
+        for e in [1.01, 1.5, 10.0] {
+            for m in [-50.0, -1.0, 0.0, 0.2, 1e3] {
+                let ha = solve_kepler_hyperbolic(m, e);
+                assert_close(e * ha.sinh() - ha, m, 1e-12);
+            }
+        }
+        for m in [-1e4, -2.0, 0.0, 0.5, 30.0] {
+            let d = solve_barker(m);
+            assert_close(d + d.powi(3) / 3.0, m, 1e-12);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+pub const DEFAULT_STEP: f64 = 1.0 / 120.0;
+pub const DEFAULT_MAX_SUBSTEPS: u32 = 16;
+//up to this warp factor time is sped up by running more steps, above it steps get longer
+pub const SUBSTEP_WARP: f64 = 4.0;
+
+//fixed timestep accumulator, physics always advances by `step`
+//independent of how long the rendered frame took
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhysicsClock {
+    //step at 1x warp
+    pub base_step: f64,
+    pub step: f64,
+    pub warp: f64,
+    pub max_substeps: u32,
+    pub accumulator: f64,
+    //simulated seconds since start
+    pub time: f64,
+    //fraction of a step left in the accumulator, used to blend previous and current state
+    pub alpha: f64,
+}
+
+impl PhysicsClock {
+    pub fn new(step: f64, max_substeps: u32) -> Self {
+        PhysicsClock {
+            base_step: step,
+            step,
+            warp: 1.0,
+            max_substeps,
+            accumulator: 0.0,
+            time: 0.0,
+            alpha: 0.0,
+        }
+    }
+
+    pub fn set_warp(&mut self, warp: f64) {
+        self.warp = warp;
+        self.step = self.base_step * (warp / SUBSTEP_WARP).max(1.0);
+    }
+
+    //consumes frame time, returns how many steps have to be simulated
+    pub fn advance(&mut self, frame_dt: f64) -> u32 {
+        self.accumulator += frame_dt * self.warp;
+        let steps = (self.accumulator / self.step).floor() as u32;
+        let steps = if steps > self.max_substeps {
+            //can't keep up, drop the backlog instead of spiralling
+            self.accumulator %= self.step;
+            self.max_substeps
+        } else {
+            self.accumulator -= steps as f64 * self.step;
+            steps
+        };
+        self.alpha = (self.accumulator / self.step).clamp(0.0, 1.0);
+        steps
+    }
+
+    pub fn tick(&mut self) {
+        self.time += self.step;
+    }
+}
+
+impl Default for PhysicsClock {
+    fn default() -> Self {
+        PhysicsClock::new(DEFAULT_STEP, DEFAULT_MAX_SUBSTEPS)
+    }
+}
@@ -0,0 +1,215 @@
+use std::str::FromStr;
+
+use crate::{
+    assembly::DEFAULT_ROCKET,
+    gravity::{Gravity, GravitySolver},
+    integrator::{AdaptiveStepping, IntegratorKind},
+    physics_clock::{DEFAULT_MAX_SUBSTEPS, DEFAULT_STEP},
+    systems::{celestial_collision::CollisionOutcome, landing::LandingTolerances},
+};
+
+pub const USAGE: &str = "usage: ksp2d [--gravity direct|barnes-hut] [--theta <opening angle>] \
+[--softening <meters>] [--integrator verlet|rk4|yoshida] [--step <seconds>] [--max-substeps <count>] [--on-rails] \
+[--adaptive <max level>] [--eta <fraction>] [--collisions merge|bounce] [--restitution <0..1>] \
+[--rocket <name in rockets/>] [--seed <number>] [--max-vertical-speed <m/s>] \
+[--max-horizontal-speed <m/s>] [--max-tilt <degrees>] [--max-slope <degrees>]";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub gravity: Gravity,
+    pub integrator: IntegratorKind,
+    pub physics_step: f64,
+    pub max_substeps: u32,
+    //planets follow fixed Kepler orbits instead of being integrated
+    pub on_rails: bool,
+    pub adaptive_stepping: AdaptiveStepping,
+    pub collision_outcome: CollisionOutcome,
+    //assembly file name without the extension
+    pub rocket: String,
+    //of the generated star system, reverting keeps it
+    pub seed: u64,
+    pub landing: LandingTolerances,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            gravity: Gravity::default(),
+            integrator: IntegratorKind::default(),
+            physics_step: DEFAULT_STEP,
+            max_substeps: DEFAULT_MAX_SUBSTEPS,
+            on_rails: false,
+            adaptive_stepping: AdaptiveStepping::disabled(),
+            collision_outcome: CollisionOutcome::default(),
+            rocket: DEFAULT_ROCKET.to_string(),
+            seed: rand::random(),
+            landing: LandingTolerances::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Settings, String> {
+        let mut settings = Settings::default();
+        let mut theta = None;
+        let mut restitution = None;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--gravity" => {
+                    settings.gravity.solver = match next_value(&mut args, &arg)?.as_str() {
+                        "direct" => GravitySolver::Direct,
+                        "barnes-hut" => GravitySolver::BarnesHut {
+                            theta: GravitySolver::DEFAULT_THETA,
+                        },
+                        other => return Err(format!("unknown gravity solver '{}'", other)),
+                    }
+                }
+                "--integrator" => {
+                    settings.integrator = match next_value(&mut args, &arg)?.as_str() {
+                        "verlet" => IntegratorKind::VelocityVerlet,
+                        "rk4" => IntegratorKind::RungeKutta4,
+                        "yoshida" => IntegratorKind::Yoshida4,
+                        other => return Err(format!("unknown integrator '{}'", other)),
+                    }
+                }
+                "--theta" => theta = Some(parse_value::<f64, _>(&mut args, &arg)?),
+                "--step" => settings.physics_step = parse_positive(&mut args, &arg)?,
+                "--max-substeps" => {
+                    settings.max_substeps = parse_value(&mut args, &arg)?;
+                    if settings.max_substeps == 0 {
+                        return Err("--max-substeps must be at least 1".to_string());
+                    }
+                }
+                "--softening" => {
+                    settings.gravity.softening = parse_value(&mut args, &arg)?;
+                    //NaN fails the comparison
+                    if !(settings.gravity.softening >= 0.0
+                        && settings.gravity.softening.is_finite())
+                    {
+                        return Err("--softening must be non-negative".to_string());
+                    }
+                }
+                "--collisions" => {
+                    settings.collision_outcome = match next_value(&mut args, &arg)?.as_str() {
+                        "merge" => CollisionOutcome::Merge,
+                        "bounce" => CollisionOutcome::Bounce { restitution: 1.0 },
+                        other => return Err(format!("unknown collision outcome '{}'", other)),
+                    }
+                }
+                "--restitution" => restitution = Some(parse_value::<f64, _>(&mut args, &arg)?),
+                "--on-rails" => settings.on_rails = true,
+                "--rocket" => settings.rocket = next_value(&mut args, &arg)?,
+                "--seed" => settings.seed = parse_value(&mut args, &arg)?,
+                "--max-vertical-speed" => {
+                    settings.landing.vertical_speed = parse_positive(&mut args, &arg)?
+                }
+                "--max-horizontal-speed" => {
+                    settings.landing.horizontal_speed = parse_positive(&mut args, &arg)?
+                }
+                "--max-tilt" => {
+                    settings.landing.tilt = parse_positive(&mut args, &arg)?.to_radians()
+                }
+                "--max-slope" => {
+                    settings.landing.slope = parse_positive(&mut args, &arg)?.to_radians()
+                }
+                "--adaptive" => {
+                    settings.adaptive_stepping.max_level = parse_value(&mut args, &arg)?;
+                    if settings.adaptive_stepping.max_level > AdaptiveStepping::LIMIT_MAX_LEVEL {
+                        return Err(format!(
+                            "--adaptive level must be at most {}",
+                            AdaptiveStepping::LIMIT_MAX_LEVEL
+                        ));
+                    }
+                }
+                "--eta" => settings.adaptive_stepping.eta = parse_positive(&mut args, &arg)?,
+                other => return Err(format!("unknown argument '{}'", other)),
+            }
+        }
+        if let Some(t) = theta {
+            match &mut settings.gravity.solver {
+                GravitySolver::BarnesHut { theta } if t >= 0.0 => *theta = t,
+                GravitySolver::BarnesHut { .. } => {
+                    return Err("--theta must be non-negative".to_string())
+                }
+                GravitySolver::Direct => {
+                    return Err("--theta requires --gravity barnes-hut".to_string())
+                }
+            }
+        }
+        if let Some(r) = restitution {
+            match &mut settings.collision_outcome {
+                CollisionOutcome::Bounce { restitution } if (0.0..=1.0).contains(&r) => {
+                    *restitution = r
+                }
+                CollisionOutcome::Bounce { .. } => {
+                    return Err("--restitution must be within 0..1".to_string())
+                }
+                CollisionOutcome::Merge => {
+                    return Err("--restitution requires --collisions bounce".to_string())
+                }
+            }
+        }
+        Ok(settings)
+    }
+}
+
+fn next_value<I: Iterator<Item = String>>(args: &mut I, name: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for {}", name))
+}
+
+fn parse_positive<I: Iterator<Item = String>>(args: &mut I, name: &str) -> Result<f64, String> {
+    let value: f64 = parse_value(args, name)?;
+    //NaN fails the comparison
+    if value > 0.0 && value.is_finite() {
+        Ok(value)
+    } else {
+        Err(format!("{} must be positive", name))
+    }
+}
+
+fn parse_value<T: FromStr, I: Iterator<Item = String>>(
+    args: &mut I,
+    name: &str,
+) -> Result<T, String> {
+    let value = next_value(args, name)?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for {}", value, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Settings, String> {
+        Settings::from_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn rejects_bad_steps() {
+        for value in ["0", "-1", "NaN", "inf"] {
+            assert!(parse(&["--step", value]).is_err(), "--step {}", value);
+        }
+        assert_eq!(parse(&["--step", "0.5"]).unwrap().physics_step, 0.5);
+    }
+
+    #[test]
+    fn rejects_bad_eta() {
+        for value in ["0", "-1", "NaN", "inf"] {
+            assert!(parse(&["--eta", value]).is_err(), "--eta {}", value);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_softening() {
+        for value in ["-1", "NaN", "inf"] {
+            assert!(
+                parse(&["--softening", value]).is_err(),
+                "--softening {}",
+                value
+            );
+        }
+        assert_eq!(parse(&["--softening", "0"]).unwrap().gravity.softening, 0.0);
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashSet;
+
+use glam::{dvec2, DVec2};
+use legion::{systems::CommandBuffer, *};
+use uom::si::{
+    f64::{Length, Velocity},
+    length::meter,
+    velocity::meter_per_second,
+};
+
+use crate::{
+    components::{
+        flight_info::FlightInfo,
+        newton_body::NewtonBody,
+        reference_body::ReferenceBody,
+        rocket::{PlayerInput, Rocket},
+        sas::Sas,
+        sphere_of_influence::SphereOfInfluence,
+        test_particle::TestParticle,
+        trajectory::Trajectory,
+    },
+    events::Events,
+    integrator::{IntegratorKind, RungeKutta4, VelocityVerlet, Yoshida4},
+    physics_clock::PhysicsClock,
+    settings::Settings,
+    system_generation::{get_system, put_on_rails},
+    systems::{
+        celestial_collision::celestial_collision_system,
+        conservation::{conservation_system, ConservationInfo},
+        drag::drag_system,
+        landing::{landing_system, vessel_collision_system, Touchdown},
+        newton_body::{begin_step_system, celestial_body_system},
+        on_rails::on_rails_system,
+        planet_resting::{liftoff_system, planet_resting_system},
+        rocket::update_positions_system,
+        sas::sas_system,
+        sphere_of_influence::{
+            reference_body_system, soi_hierarchy_system, SoiChange, SoiHierarchyStale,
+        },
+        staging::{debris_crash_system, staging_system},
+        time_warp::TimeWarp,
+    },
+    Dt, SPACE_SIZE,
+};
+
+//generated star system with the rocket as the last entity
+pub fn initial_world(settings: &Settings, rocket: Rocket) -> World {
+    let mut world = World::default();
+    let rocket_pos = dvec2(SPACE_SIZE / 8.0, SPACE_SIZE / 8.0);
+    let rocket_body = NewtonBody {
+        angle: DVec2::Y,
+        angular_vel: 0.0,
+        inertia: rocket.inertia(),
+        mass: rocket.mass(),
+        pos: rocket_pos,
+        vel: DVec2::ZERO,
+        acc: DVec2::ZERO,
+        force: DVec2::ZERO,
+        torque: 0.0,
+        prev_pos: rocket_pos,
+        prev_angle: DVec2::Y,
+    };
+
+    let sys = get_system(SPACE_SIZE * 0.5, settings.seed);
+    let first_celestial = world
+        .extend(
+            sys.into_iter()
+                .map(|(c_body, n_body)| (c_body, n_body, SphereOfInfluence::ROOT)),
+        )
+        .to_vec();
+    if settings.on_rails {
+        put_on_rails(&mut world, &first_celestial);
+    }
+    let first_celestial_enity = *first_celestial.first().unwrap();
+    world.push((
+        rocket,
+        rocket_body,
+        TestParticle,
+        ReferenceBody {
+            id: first_celestial_enity,
+            closest_surface_point: DVec2::ZERO,
+            closest_surface_point_a: DVec2::ZERO,
+        },
+        FlightInfo {
+            delta: Velocity::new::<meter_per_second>(0.0),
+            distance: Length::new::<meter>(0.0),
+            orbit: None,
+            periapsis: None,
+            apoapsis: None,
+        },
+        Trajectory::default(),
+        Sas::default(),
+    ));
+    world
+}
+
+//everything the physics schedule reads, frontends add their own on top
+pub fn initial_resources(world: &World, settings: &Settings) -> Resources {
+    let mut resources = Resources::default();
+    insert_resources(&mut resources, world, settings);
+    resources
+}
+
+//replaces the physics resources and keeps the rest
+fn insert_resources(resources: &mut Resources, world: &World, settings: &Settings) {
+    resources.insert(HashSet::<PlayerInput>::new());
+    resources.insert(Dt(settings.physics_step));
+    resources.insert(PhysicsClock::new(
+        settings.physics_step,
+        settings.max_substeps,
+    ));
+    resources.insert(TimeWarp::new());
+    resources.insert(ConservationInfo::new());
+    resources.insert(Events::<SoiChange>::new());
+    //the bodies of a new world are placed as roots
+    resources.insert(SoiHierarchyStale(true));
+    resources.insert(Events::<Touchdown>::new());
+    resources.insert(settings.landing);
+    resources.insert(settings.gravity);
+    resources.insert(settings.adaptive_stepping);
+    resources.insert(settings.collision_outcome);
+
+    let command_buffer = CommandBuffer::new(world);
+    resources.insert(command_buffer);
+}
+
+//systems executed once per physics step
+pub fn physics_schedule(settings: &Settings) -> Schedule {
+    let mut physics_builder = Schedule::builder();
+    physics_builder
+        .add_system(begin_step_system())
+        .add_system(sas_system())
+        .add_system(update_positions_system())
+        .add_system(staging_system())
+        .add_system(drag_system())
+        .add_system(on_rails_system());
+    match settings.integrator {
+        IntegratorKind::VelocityVerlet => {
+            physics_builder.add_system(celestial_body_system::<VelocityVerlet>())
+        }
+        IntegratorKind::RungeKutta4 => {
+            physics_builder.add_system(celestial_body_system::<RungeKutta4>())
+        }
+        IntegratorKind::Yoshida4 => physics_builder.add_system(celestial_body_system::<Yoshida4>()),
+    };
+    physics_builder
+        .add_system(celestial_collision_system())
+        //absorbed bodies must be gone before anything looks them up
+        .flush()
+        .add_system(landing_system())
+        .add_system(vessel_collision_system())
+        .add_system(liftoff_system())
+        .add_system(planet_resting_system())
+        .add_system(soi_hierarchy_system())
+        .add_system(reference_body_system())
+        .add_system(debris_crash_system())
+        .add_system(conservation_system())
+        .build()
+}
+
+//world, resources and physics schedule, steppable without a window
+pub struct Simulation {
+    pub world: World,
+    pub resources: Resources,
+    schedule: Schedule,
+    //to start the flight over
+    settings: Settings,
+    rocket: Rocket,
+}
+
+impl Simulation {
+    pub fn new(settings: &Settings, rocket: Rocket) -> Self {
+        let world = initial_world(settings, rocket.clone());
+        let resources = initial_resources(&world, settings);
+        Simulation {
+            world,
+            resources,
+            schedule: physics_schedule(settings),
+            settings: settings.clone(),
+            rocket,
+        }
+    }
+
+    //back to the launch in the same star system, resources added by the
+    //frontend are kept
+    pub fn revert(&mut self) {
+        self.world = initial_world(&self.settings, self.rocket.clone());
+        insert_resources(&mut self.resources, &self.world, &self.settings);
+    }
+
+    //advances the world by one step of the clock
+    pub fn step(&mut self) {
+        let step = self.resources.get::<PhysicsClock>().unwrap().step;
+        self.resources.get_mut::<Dt>().unwrap().0 = step;
+        self.schedule.execute(&mut self.world, &mut self.resources);
+        self.resources.get_mut::<PhysicsClock>().unwrap().tick();
+    }
+
+    //simulated time in seconds
+    pub fn time(&self) -> f64 {
+        self.resources.get::<PhysicsClock>().unwrap().time
+    }
+
+    //events piled up by `step` since the last drain, nothing else empties
+    //them without the frame schedule of a frontend
+    pub fn drain_soi_changes(&mut self) -> Vec<SoiChange> {
+        self.resources
+            .get_mut::<Events<SoiChange>>()
+            .unwrap()
+            .drain()
+            .collect()
+    }
+
+    pub fn drain_touchdowns(&mut self) -> Vec<Touchdown> {
+        self.resources
+            .get_mut::<Events<Touchdown>>()
+            .unwrap()
+            .drain()
+            .collect()
+    }
+}
@@ -1,15 +1,46 @@
 use std::f64::consts::PI;
 
 use glam::{dvec2, DVec2};
-use rand::Rng;
-use sdl2::pixels::Color;
+use legion::{Entity, World};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::ksp2d::components::{
-    celestial_body::{CelestialBody, CelestialBodyType},
-    newton_body::NewtonBody,
+use crate::{
+    color::Color,
+    components::{
+        celestial_body::{
+            Atmosphere, CelestialBody, CelestialBodyType, Crater, LandingZone, Terrain, CRATERS,
+            LANDING_ZONES,
+        },
+        newton_body::NewtonBody,
+        on_rails::OnRails,
+    },
+    gravity::G,
+    orbit::OrbitalElements,
 };
 
-pub fn get_system(system_radius: f64) -> Vec<(CelestialBody, NewtonBody)> {
+const GAS_CONSTANT: f64 = 8.314_462_618;
+//escape velocity below which a rocky planet can't hold an atmosphere
+const MIN_ATMOSPHERE_ESCAPE_VELOCITY: f64 = 5e3;
+//density of a planet made mostly of hydrogen and helium
+const GAS_GIANT_DENSITY: f64 = 2000.0;
+//density left at the cutoff is e^-20 of the surface one
+const ATMOSPHERE_CUTOFF_SCALE_HEIGHTS: f64 = 20.0;
+//sidereal day of the Sun, s
+const STAR_ROTATION_PERIOD: f64 = 25.4 * 86400.0;
+//sidereal days of the planets, s
+const PLANET_ROTATION_PERIODS: std::ops::RangeInclusive<f64> = 36000.0..=360000.0;
+//highest mountains as a share of the radius
+const TERRAIN_RELIEF: std::ops::RangeInclusive<f64> = 0.0005..=0.003;
+const MOUNTAIN_RANGES: std::ops::RangeInclusive<u32> = 6..=16;
+//radians
+const CRATER_HALF_WIDTHS: std::ops::RangeInclusive<f64> = 0.003..=0.015;
+//depth of a crater as a share of its width
+const CRATER_DEPTHS: std::ops::RangeInclusive<f64> = 0.05..=0.15;
+//radians
+const LANDING_ZONE_HALF_WIDTHS: std::ops::RangeInclusive<f64> = 0.002..=0.005;
+
+//the same seed always generates the same system
+pub fn get_system(system_radius: f64, seed: u64) -> Vec<(CelestialBody, NewtonBody)> {
     let system_center = dvec2(system_radius, system_radius);
 
     let planet_range = 0.01..=0.04;
@@ -20,25 +51,33 @@ pub fn get_system(system_radius: f64) -> Vec<(CelestialBody, NewtonBody)> {
     let star_radius = 6.957e8 / 8.0;
     let star_mass = 1.988416e30;
 
+    let star_body = CelestialBody {
+        b_type: CelestialBodyType::Star,
+        color: Color::YELLOW,
+        radius: star_radius,
+        atmosphere: None,
+        terrain: None,
+    };
     let star = (
-        CelestialBody {
-            b_type: CelestialBodyType::Star,
-            color: Color::YELLOW,
-            radius: star_radius,
-        },
+        star_body,
         NewtonBody {
             angle: DVec2::Y,
-            angular_vel: 1.0,
+            angular_vel: 2.0 * PI / STAR_ROTATION_PERIOD,
+            inertia: star_body.inertia(star_mass),
             mass: star_mass,
             pos: system_center,
             vel: DVec2::ZERO,
             acc: DVec2::ZERO,
+            force: DVec2::ZERO,
+            torque: 0.0,
+            prev_pos: system_center,
+            prev_angle: DVec2::Y,
         },
     );
     let mut system: Vec<(CelestialBody, NewtonBody)> = Vec::new();
     system.push(star);
 
-    let mut rng = rand::rng();
+    let mut rng = StdRng::seed_from_u64(seed);
     let mut cursor = star_radius;
     let limit =
         system_radius - (system_radius * planet_range.end() + system_radius * interval_range.end());
@@ -56,19 +95,27 @@ pub fn get_system(system_radius: f64) -> Vec<(CelestialBody, NewtonBody)> {
 
         let velocity = DVec2::new(-position.y, position.x).normalize() * orbital_speed;
 
+        let planet_body = CelestialBody {
+            b_type: CelestialBodyType::Planet,
+            color: Color::GREEN,
+            radius: p_r,
+            atmosphere: generate_atmosphere(&mut rng, mass, dencity, p_r),
+            terrain: generate_terrain(&mut rng, dencity, p_r),
+        };
         let planet = (
-            CelestialBody {
-                b_type: CelestialBodyType::Planet,
-                color: Color::GREEN,
-                radius: p_r,
-            },
+            planet_body,
             NewtonBody {
                 angle: DVec2::Y,
-                angular_vel: angle,
+                angular_vel: 2.0 * PI / rng.random_range(PLANET_ROTATION_PERIODS),
+                inertia: planet_body.inertia(mass),
                 mass: mass,
                 pos: system_center + position,
                 vel: velocity,
                 acc: DVec2::ZERO,
+                force: DVec2::ZERO,
+                torque: 0.0,
+                prev_pos: system_center + position,
+                prev_angle: DVec2::Y,
             },
         );
         system.push(planet);
@@ -77,6 +124,82 @@ pub fn get_system(system_radius: f64) -> Vec<(CelestialBody, NewtonBody)> {
     system
 }
 
+//star stays where it is, planets follow the orbits they were generated on,
+//expects entities in `get_system` order
+pub fn put_on_rails(world: &mut World, system: &[Entity]) {
+    let (star, planets) = system.split_first().unwrap();
+    let mut star_entry = world.entry(*star).unwrap();
+    let star_body = *star_entry.get_component::<NewtonBody>().unwrap();
+    star_entry.add_component(OnRails::Fixed);
+    for planet in planets {
+        let mut entry = world.entry(*planet).unwrap();
+        let n_body = *entry.get_component::<NewtonBody>().unwrap();
+        let elements = OrbitalElements::from_state_vectors(
+            n_body.pos - star_body.pos,
+            n_body.vel - star_body.vel,
+            G * star_body.mass,
+            0.0,
+        );
+        entry.add_component(OnRails::Orbit {
+            parent: *star,
+            elements,
+        });
+    }
+}
+
+//ideal gas in hydrostatic equilibrium at a random temperature and surface pressure
+fn generate_atmosphere(
+    rng: &mut impl Rng,
+    mass: f64,
+    density: f64,
+    radius: f64,
+) -> Option<Atmosphere> {
+    let gas_giant = density < GAS_GIANT_DENSITY;
+    let escape_velocity = (2.0 * G * mass / radius).sqrt();
+    if !gas_giant && (escape_velocity < MIN_ATMOSPHERE_ESCAPE_VELOCITY || rng.random_bool(0.5)) {
+        return None;
+    }
+    let (molar_mass, pressure) = if gas_giant {
+        (0.0023, rng.random_range(1e5..=1e6))
+    } else {
+        (rng.random_range(0.028..=0.044), rng.random_range(1e3..=1e7))
+    };
+    let temperature = rng.random_range(100.0..=800.0);
+    let surface_gravity = G * mass / (radius * radius);
+    let scale_height = GAS_CONSTANT * temperature / (molar_mass * surface_gravity);
+    Some(Atmosphere {
+        scale_height,
+        surface_density: pressure * molar_mass / (GAS_CONSTANT * temperature),
+        cutoff_altitude: scale_height * ATMOSPHERE_CUTOFF_SCALE_HEIGHTS,
+    })
+}
+
+//rocky planets get mountains, craters and a few flat landing zones
+fn generate_terrain(rng: &mut impl Rng, density: f64, radius: f64) -> Option<Terrain> {
+    if density < GAS_GIANT_DENSITY {
+        return None;
+    }
+    let craters = [(); CRATERS].map(|_| {
+        let half_width = rng.random_range(CRATER_HALF_WIDTHS);
+        Crater {
+            angle: rng.random_range(0.0..2.0 * PI),
+            half_width,
+            depth: 2.0 * half_width * radius * rng.random_range(CRATER_DEPTHS),
+        }
+    });
+    let landing_zones = [(); LANDING_ZONES].map(|_| LandingZone {
+        angle: rng.random_range(0.0..2.0 * PI),
+        half_width: rng.random_range(LANDING_ZONE_HALF_WIDTHS),
+    });
+    Some(Terrain {
+        seed: rng.random(),
+        amplitude: radius * rng.random_range(TERRAIN_RELIEF),
+        ranges: rng.random_range(MOUNTAIN_RANGES),
+        craters,
+        landing_zones,
+    })
+}
+
 fn calculate_radius(mass: f64, density: f64) -> f64 {
     let volume = mass / density;
     let radius_cubed = (3.0 * volume) / (4.0 * PI);
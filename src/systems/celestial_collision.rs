@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use glam::DVec2;
+use legion::{systems::CommandBuffer, world::SubWorld, *};
+
+use crate::{
+    collision::{circle_aabb, is_aabb_intersected, is_circles_intersected},
+    components::{
+        celestial_body::CelestialBody, landing::LandingRelation, newton_body::NewtonBody,
+        on_rails::OnRails, reference_body::ReferenceBody,
+    },
+    gravity::G,
+    orbit::OrbitalElements,
+    physics_clock::PhysicsClock,
+    systems::sphere_of_influence::SoiHierarchyStale,
+    Dt,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CollisionOutcome {
+    //perfectly inelastic, the lighter body is absorbed conserving mass, momentum and volume
+    #[default]
+    Merge,
+    //bodies are pushed apart, restitution 1.0 is perfectly elastic
+    Bounce {
+        restitution: f64,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Body {
+    id: Entity,
+    c_body: CelestialBody,
+    n_body: NewtonBody,
+    rails: Option<OnRails>,
+    changed: bool,
+}
+
+impl Body {
+    //bodies on rails are not pushed around by collisions
+    fn inverse_mass(&self) -> f64 {
+        match self.rails {
+            Some(_) => 0.0,
+            None => self.n_body.mass.recip(),
+        }
+    }
+}
+
+#[system]
+#[read_component(Entity)]
+#[write_component(CelestialBody)]
+#[write_component(NewtonBody)]
+#[write_component(OnRails)]
+#[write_component(ReferenceBody)]
+#[write_component(LandingRelation)]
+pub fn celestial_collision(
+    world: &mut SubWorld,
+    command_buffer: &mut CommandBuffer,
+    #[resource] outcome: &CollisionOutcome,
+    #[resource] clock: &PhysicsClock,
+    #[resource] dt: &Dt,
+    #[resource] soi_stale: &mut SoiHierarchyStale,
+) {
+    let mut query = <(Entity, &CelestialBody, &NewtonBody, Option<&OnRails>)>::query();
+    let mut bodies: Vec<_> = query
+        .iter(world)
+        .map(|(id, c_body, n_body, rails)| Body {
+            id: *id,
+            c_body: *c_body,
+            n_body: *n_body,
+            rails: rails.copied(),
+            changed: false,
+        })
+        .collect();
+
+    //absorbed body -> body it was merged into
+    let mut absorbed = HashMap::new();
+    for j in 1..bodies.len() {
+        let (head, tail) = bodies.split_at_mut(j);
+        let b = &mut tail[0];
+        for a in head.iter_mut() {
+            if absorbed.contains_key(&a.id) || absorbed.contains_key(&b.id) {
+                continue;
+            }
+            let (a_lt, a_rb) = circle_aabb(a.n_body.pos, a.c_body.radius);
+            let (b_lt, b_rb) = circle_aabb(b.n_body.pos, b.c_body.radius);
+            if !is_aabb_intersected(a_lt, a_rb, b_lt, b_rb)
+                || !is_circles_intersected(
+                    a.n_body.pos,
+                    a.c_body.radius,
+                    b.n_body.pos,
+                    b.c_body.radius,
+                )
+            {
+                continue;
+            }
+            match *outcome {
+                CollisionOutcome::Merge => {
+                    let (survivor, loser) = if a.n_body.mass >= b.n_body.mass {
+                        (&mut *a, &*b)
+                    } else {
+                        (&mut *b, &*a)
+                    };
+                    merge(survivor, loser);
+                    absorbed.insert(loser.id, survivor.id);
+                }
+                CollisionOutcome::Bounce { restitution } => bounce(a, b, restitution),
+            }
+        }
+    }
+
+    let resolve = |mut id: Entity| {
+        while let Some(next) = absorbed.get(&id) {
+            id = *next;
+        }
+        id
+    };
+    let states: HashMap<_, _> = bodies
+        .iter()
+        .map(|b| (b.id, (b.n_body.pos, b.n_body.vel, b.n_body.mass)))
+        .collect();
+    //collisions happen at the end of the step
+    let epoch = clock.time + dt.0;
+
+    for body in bodies.iter_mut() {
+        if absorbed.contains_key(&body.id) {
+            command_buffer.remove(body.id);
+            continue;
+        }
+        if let Some(OnRails::Orbit { parent, .. }) = body.rails {
+            let new_parent = resolve(parent);
+            if body.changed || new_parent != parent {
+                let (p_pos, p_vel, p_mass) = states[&new_parent];
+                body.rails = Some(OnRails::Orbit {
+                    parent: new_parent,
+                    elements: OrbitalElements::from_state_vectors(
+                        body.n_body.pos - p_pos,
+                        body.n_body.vel - p_vel,
+                        G * p_mass,
+                        epoch,
+                    ),
+                });
+                body.changed = true;
+            }
+        }
+        if body.changed {
+            let mut entry = world.entry_mut(body.id).unwrap();
+            *entry.get_component_mut::<CelestialBody>().unwrap() = body.c_body;
+            *entry.get_component_mut::<NewtonBody>().unwrap() = body.n_body;
+            if let (Some(rails), Ok(r)) = (body.rails, entry.get_component_mut::<OnRails>()) {
+                *r = rails;
+            }
+        }
+    }
+
+    if absorbed.is_empty() {
+        return;
+    }
+    soi_stale.0 = true;
+    for reference in <&mut ReferenceBody>::query().iter_mut(world) {
+        reference.id = resolve(reference.id);
+    }
+    for landing in <&mut LandingRelation>::query().iter_mut(world) {
+        landing.planet_id = resolve(landing.planet_id);
+    }
+}
+
+fn merge(survivor: &mut Body, loser: &Body) {
+    let (s, l) = (&mut survivor.n_body, &loser.n_body);
+    let mass = s.mass + l.mass;
+    //a fixed body stays where it is and takes the momentum with it
+    if survivor.rails != Some(OnRails::Fixed) {
+        s.pos = (s.pos * s.mass + l.pos * l.mass) / mass;
+        s.vel = (s.vel * s.mass + l.vel * l.mass) / mass;
+    }
+    s.mass = mass;
+    survivor.c_body.radius =
+        CelestialBody::radius_for_volume(survivor.c_body.volume() + loser.c_body.volume());
+    s.inertia = survivor.c_body.inertia(mass);
+    survivor.changed = true;
+}
+
+fn bounce(a: &mut Body, b: &mut Body, restitution: f64) {
+    let (inv_a, inv_b) = (a.inverse_mass(), b.inverse_mass());
+    let inv_sum = inv_a + inv_b;
+    if inv_sum == 0.0 {
+        return;
+    }
+    let delta = b.n_body.pos - a.n_body.pos;
+    let normal = delta.try_normalize().unwrap_or(DVec2::X);
+    let overlap = a.c_body.radius + b.c_body.radius - delta.length();
+    a.n_body.pos -= normal * overlap * inv_a / inv_sum;
+    b.n_body.pos += normal * overlap * inv_b / inv_sum;
+
+    let approach = (a.n_body.vel - b.n_body.vel).dot(normal);
+    if approach > 0.0 {
+        let impulse = (1.0 + restitution) * approach / inv_sum;
+        a.n_body.vel -= normal * impulse * inv_a;
+        b.n_body.vel += normal * impulse * inv_b;
+    }
+    a.changed = true;
+    b.changed = true;
+}
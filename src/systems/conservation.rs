@@ -0,0 +1,115 @@
+use glam::DVec2;
+use legion::{world::SubWorld, *};
+
+use crate::{
+    components::{landing::LandingRelation, newton_body::NewtonBody, test_particle::TestParticle},
+    gravity::Gravity,
+    systems::newton_body::{calculate_angular_momentum, calculate_energy, calculate_momentum},
+};
+
+//the O(n^2) energy is summed every this many steps, starting with the first
+const SAMPLE_EVERY: u32 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConservedQuantities {
+    pub energy: f64,
+    pub momentum: DVec2,
+    pub angular_momentum: f64,
+    //sum of |p| of all bodies, scale for the momentum drift since total momentum may be ~0
+    pub momentum_scale: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConservationInfo {
+    pub current: Option<ConservedQuantities>,
+    pub initial: Option<ConservedQuantities>,
+    //bodies in the simulated set, baseline is taken again when it changes
+    pub body_count: usize,
+    pub energy_drift: f64,
+    pub momentum_drift: f64,
+    pub angular_momentum_drift: f64,
+    //steps since the start
+    pub steps: u32,
+    //of the panel only, the drift is tracked either way
+    pub visible: bool,
+}
+
+impl ConservationInfo {
+    pub fn new() -> Self {
+        ConservationInfo {
+            current: None,
+            initial: None,
+            body_count: 0,
+            energy_drift: 0.0,
+            momentum_drift: 0.0,
+            angular_momentum_drift: 0.0,
+            steps: 0,
+            visible: false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.initial = self.current;
+        self.energy_drift = 0.0;
+        self.momentum_drift = 0.0;
+        self.angular_momentum_drift = 0.0;
+    }
+}
+
+impl Default for ConservationInfo {
+    fn default() -> Self {
+        ConservationInfo::new()
+    }
+}
+
+#[inline]
+fn relative(now: f64, initial: f64) -> f64 {
+    if initial == 0.0 {
+        0.0
+    } else {
+        ((now - initial) / initial).abs()
+    }
+}
+
+//test particles are left out, they exchange energy with the system one way only,
+//the baseline is taken on the first step
+#[system]
+#[read_component(NewtonBody)]
+pub fn conservation(
+    world: &SubWorld,
+    #[resource] gravity: &Gravity,
+    #[resource] info: &mut ConservationInfo,
+) {
+    let sample = info.steps % SAMPLE_EVERY == 0;
+    info.steps += 1;
+    if !sample {
+        return;
+    }
+    let mut query = <&NewtonBody>::query()
+        .filter(!component::<LandingRelation>() & !component::<TestParticle>());
+    let bodies: Vec<_> = query.iter(world).collect();
+    let current = ConservedQuantities {
+        energy: calculate_energy(&bodies, gravity.softening),
+        momentum: calculate_momentum(&bodies),
+        angular_momentum: calculate_angular_momentum(&bodies),
+        momentum_scale: bodies.iter().map(|b| b.mass * b.vel.length()).sum(),
+    };
+    info.current = Some(current);
+
+    match info.initial {
+        Some(initial) if info.body_count == bodies.len() => {
+            info.energy_drift = relative(current.energy, initial.energy);
+            info.momentum_drift = if initial.momentum_scale == 0.0 {
+                0.0
+            } else {
+                (current.momentum - initial.momentum).length() / initial.momentum_scale
+            };
+            info.angular_momentum_drift =
+                relative(current.angular_momentum, initial.angular_momentum);
+        }
+        _ => {
+            info.body_count = bodies.len();
+            info.reset();
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use legion::{world::SubWorld, *};
+
+use crate::{
+    components::{
+        celestial_body::CelestialBody, landing::LandingRelation, newton_body::NewtonBody,
+        reference_body::ReferenceBody, rocket::Rocket,
+    },
+    Dt,
+};
+
+#[system]
+#[read_component(CelestialBody)]
+#[read_component(ReferenceBody)]
+#[read_component(Rocket)]
+#[write_component(NewtonBody)]
+pub fn drag(world: &mut SubWorld, #[resource] dt: &Dt) {
+    let mut query = <(Entity, &CelestialBody, &NewtonBody)>::query();
+    let atmospheres: HashMap<_, _> = query
+        .iter(world)
+        .filter_map(|(id, c_body, n_body)| {
+            c_body
+                .atmosphere
+                .map(|atmosphere| (*id, (atmosphere, c_body.radius, *n_body)))
+        })
+        .collect();
+
+    let mut query = <(&Rocket, &mut NewtonBody, &ReferenceBody)>::query()
+        .filter(!component::<LandingRelation>());
+    for (rocket, n_body, reference) in query.iter_mut(world) {
+        let (atmosphere, radius, planet) = match atmospheres.get(&reference.id) {
+            Some(a) => a,
+            None => continue,
+        };
+        let r = n_body.pos - planet.pos;
+        let density = atmosphere.density_at(r.length() - radius);
+        if density == 0.0 {
+            continue;
+        }
+        //the atmosphere rotates together with the planet
+        let wind = planet.vel + planet.angular_vel * r.perp();
+        let v_rel = n_body.vel - wind;
+        let speed = v_rel.length();
+        let mut drag = 0.5 * density * speed * rocket.drag_coefficient * rocket.drag_area;
+        //drag can at most stop the rocket relative to the air within one step
+        drag = drag.min(n_body.mass / dt.0);
+        n_body.force -= v_rel * drag;
+    }
+}
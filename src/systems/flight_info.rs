@@ -0,0 +1,52 @@
+use legion::{world::SubWorld, *};
+use uom::si::{
+    f64::{Length, Velocity},
+    length::meter,
+    velocity::meter_per_second,
+};
+
+use crate::{
+    components::{
+        celestial_body::CelestialBody, flight_info::FlightInfo, newton_body::NewtonBody,
+        reference_body::ReferenceBody, rocket::Rocket,
+    },
+    gravity::G,
+    orbit::OrbitalElements,
+};
+
+#[system(for_each)]
+#[read_component(NewtonBody)]
+#[read_component(CelestialBody)]
+#[write_component(FlightInfo)]
+pub fn flight_info(
+    world: &SubWorld,
+    _: &Rocket,
+    n_body: &NewtonBody,
+    reference: &ReferenceBody,
+    info: &mut FlightInfo,
+) {
+    info.delta = Velocity::new::<meter_per_second>(n_body.vel.length());
+    info.distance = Length::new::<meter>(n_body.pos.distance(reference.closest_surface_point));
+
+    let reference_body = world.entry_ref(reference.id).unwrap();
+    let planet = reference_body.get_component::<NewtonBody>().unwrap();
+    let radius = reference_body
+        .get_component::<CelestialBody>()
+        .unwrap()
+        .radius;
+    let orbit = OrbitalElements::from_state_vectors(
+        n_body.pos - planet.pos,
+        n_body.vel - planet.vel,
+        G * planet.mass,
+        0.0,
+    );
+    //a body sitting on the center of the planet has no orbit
+    info.orbit = Some(orbit).filter(|o| o.semi_latus_rectum > 0.0);
+    info.periapsis = info
+        .orbit
+        .map(|o| Length::new::<meter>(o.periapsis() - radius));
+    info.apoapsis = info
+        .orbit
+        .and_then(|o| o.apoapsis())
+        .map(|apoapsis| Length::new::<meter>(apoapsis - radius));
+}
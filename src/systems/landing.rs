@@ -0,0 +1,824 @@
+use glam::DVec2;
+use legion::{world::SubWorld, *};
+use rand::Rng;
+use systems::CommandBuffer;
+
+use crate::{
+    collision::{
+        circle_aabb, is_aabb_intersected, is_segment_intersects_circle, is_triangles_intersected,
+        segment_circle_time_of_impact, segment_normal, triangle_aabb, triangles_penetration,
+    },
+    components::{
+        celestial_body::CelestialBody,
+        debris::Debris,
+        landing::{Crashed, Damaged, LandingRelation},
+        newton_body::NewtonBody,
+        reference_body::ReferenceBody,
+        rocket::Rocket,
+        spent_stage::{Separating, SpentStage},
+        test_particle::TestParticle,
+    },
+    events::Events,
+    physics_clock::PhysicsClock,
+    Dt,
+};
+
+//a hard landing damages the rocket up to this multiple of the tolerances
+const HARD_LANDING_FACTOR: f64 = 2.0;
+const DEBRIS_PIECES: usize = 8;
+//share of the impact speed the debris bounces off with
+const DEBRIS_RESTITUTION: f64 = 0.3;
+//debris is scattered within this angle around the bounce direction
+const DEBRIS_SPREAD: f64 = std::f64::consts::FRAC_PI_4;
+
+//worst touchdown that still counts as a landing
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LandingTolerances {
+    //towards the surface, m/s
+    pub vertical_speed: f64,
+    //along the surface, m/s
+    pub horizontal_speed: f64,
+    //between the nose and the local vertical, radians
+    pub tilt: f64,
+    //between the terrain and the local horizontal, radians
+    pub slope: f64,
+}
+
+impl Default for LandingTolerances {
+    fn default() -> Self {
+        LandingTolerances {
+            vertical_speed: 6.0,
+            horizontal_speed: 3.0,
+            tilt: 10f64.to_radians(),
+            slope: 15f64.to_radians(),
+        }
+    }
+}
+
+impl LandingTolerances {
+    pub fn classify(
+        &self,
+        vertical_speed: f64,
+        horizontal_speed: f64,
+        tilt: f64,
+        slope: f64,
+    ) -> LandingOutcome {
+        let worst = (vertical_speed / self.vertical_speed)
+            .max(horizontal_speed / self.horizontal_speed)
+            .max(tilt / self.tilt)
+            .max(slope / self.slope);
+        if worst <= 1.0 {
+            LandingOutcome::Landed
+        } else if worst <= HARD_LANDING_FACTOR {
+            LandingOutcome::HardLanded
+        } else {
+            LandingOutcome::Crashed
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LandingOutcome {
+    Landed,
+    //landed, but the rocket is damaged
+    HardLanded,
+    //the rocket is destroyed
+    Crashed,
+}
+
+//sent when a rocket reaches the surface, speeds are relative to the ground
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Touchdown {
+    pub entity: Entity,
+    pub planet: Entity,
+    pub outcome: LandingOutcome,
+    pub vertical_speed: f64,
+    pub horizontal_speed: f64,
+    pub tilt: f64,
+    //of the terrain, radians
+    pub slope: f64,
+    pub time: f64,
+}
+
+//contact with the reference body at the end of the step, or the first body
+//the rocket passed into during the step, in which case it is moved back to
+//the point of impact when it touches down
+#[system]
+#[read_component(Rocket)]
+#[read_component(CelestialBody)]
+#[read_component(ReferenceBody)]
+#[read_component(LandingRelation)]
+#[read_component(Crashed)]
+#[write_component(NewtonBody)]
+pub fn landing(
+    world: &mut SubWorld,
+    command_buffer: &mut CommandBuffer,
+    #[resource] tolerances: &LandingTolerances,
+    #[resource] events: &mut Events<Touchdown>,
+    #[resource] clock: &PhysicsClock,
+    #[resource] dt: &Dt,
+) {
+    let bodies: Vec<_> = <(Entity, &CelestialBody, &NewtonBody)>::query()
+        .iter(world)
+        .map(|(id, c_body, n_body)| (*id, *c_body, *n_body))
+        .collect();
+
+    let mut query = <(Entity, &Rocket, &mut NewtonBody, &ReferenceBody)>::query()
+        .filter(!component::<LandingRelation>() & !component::<Crashed>());
+    for (e, rocket, n_body, reference) in query.iter_mut(world) {
+        let ((planet_id, celestial_comp, newton_body_comp), pos) =
+            match swept_contact(n_body, &bodies).or_else(|| {
+                bodies
+                    .iter()
+                    .find(|(id, c_body, planet)| {
+                        *id == reference.id
+                            && hull_touches_surface(
+                                &rocket.hull().map(|p| n_body.pos + n_body.angle.rotate(p)),
+                                c_body,
+                                planet,
+                            )
+                    })
+                    .map(|body| (body, n_body.pos))
+            }) {
+                Some(((id, c_body, planet), pos)) => ((*id, c_body, planet), pos),
+                None => continue,
+            };
+
+        let r = pos - newton_body_comp.pos;
+        let (seg_p1, seg_p2) =
+            celestial_comp.surface_segment(newton_body_comp.to_local(r).normalize_or_zero());
+        let up = r.normalize_or_zero();
+        //perpendicular to the terrain below the rocket
+        let normal = newton_body_comp
+            .angle
+            .rotate(segment_normal(seg_p1, seg_p2));
+        //the ground moves with the rotation of the planet
+        let ground = newton_body_comp.vel + newton_body_comp.angular_vel * r.perp();
+        let v_rel = n_body.vel - ground;
+        //climbing away from the surface, e.g. right after liftoff
+        if v_rel.dot(normal) > 0.0 {
+            continue;
+        }
+        //only a recorded touchdown moves the rocket back to the point of impact
+        n_body.pos = pos;
+        let (vertical_speed, horizontal_speed) = impact_speeds(v_rel, normal);
+        let tilt = n_body.angle.rotate(DVec2::NEG_Y).angle_to(up).abs();
+        let slope = normal.angle_to(up).abs();
+        let outcome = tolerances.classify(vertical_speed, horizontal_speed, tilt, slope);
+        events.send(Touchdown {
+            entity: *e,
+            planet: planet_id,
+            outcome,
+            vertical_speed,
+            horizontal_speed,
+            tilt,
+            slope,
+            time: clock.time + dt.0,
+        });
+
+        let local_up = newton_body_comp.to_local(up);
+        command_buffer.add_component(
+            *e,
+            LandingRelation {
+                planet_id,
+                angle_position: local_up,
+                attitude: newton_body_comp.to_local(n_body.angle),
+            },
+        );
+        match outcome {
+            LandingOutcome::Landed => {}
+            LandingOutcome::HardLanded => command_buffer.add_component(*e, Damaged),
+            LandingOutcome::Crashed => {
+                command_buffer.add_component(*e, Crashed);
+                let surface = newton_body_comp.pos + up * celestial_comp.surface_radius(local_up);
+                let bounce = (v_rel - 2.0 * v_rel.dot(normal) * normal) * DEBRIS_RESTITUTION;
+                spawn_debris(
+                    command_buffer,
+                    n_body,
+                    &rocket.hull(),
+                    &ReferenceBody {
+                        id: planet_id,
+                        closest_surface_point: surface,
+                        closest_surface_point_a: up,
+                    },
+                    surface + up,
+                    ground,
+                    bounce,
+                );
+            }
+        }
+    }
+}
+
+//towards and along the ground with the `normal` of the terrain, for a
+//velocity relative to the ground
+fn impact_speeds(v_rel: DVec2, normal: DVec2) -> (f64, f64) {
+    (-v_rel.dot(normal), v_rel.perp_dot(normal).abs())
+}
+
+//first body whose ground the center of the rocket crossed during the step,
+//relative to the motion of the body, with the position of the crossing
+fn swept_contact<'a>(
+    n_body: &NewtonBody,
+    bodies: &'a [(Entity, CelestialBody, NewtonBody)],
+) -> Option<(&'a (Entity, CelestialBody, NewtonBody), DVec2)> {
+    let (time, body, impact) = bodies
+        .iter()
+        .filter_map(|body| {
+            let (_, c_body, planet) = body;
+            let start = n_body.prev_pos - planet.prev_pos;
+            let end = n_body.pos - planet.pos;
+            if !is_segment_intersects_circle(start, end, DVec2::ZERO, c_body.max_surface_radius()) {
+                return None;
+            }
+            //the ground where the rocket ended up, then where it went in
+            let ground = c_body.surface_radius(planet.to_local(end).normalize_or_zero());
+            let time = segment_circle_time_of_impact(start, end, DVec2::ZERO, ground)?;
+            let impact = start.lerp(end, time);
+            let ground = c_body.surface_radius(planet.to_local(impact).normalize_or_zero());
+            let time =
+                segment_circle_time_of_impact(start, end, DVec2::ZERO, ground).unwrap_or(time);
+            Some((time, body, start.lerp(end, time)))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))?;
+    log::debug!("swept into {:?} at {:.3} of the step", body.0, time);
+    Some((body, body.2.pos + impact))
+}
+
+//what collided, for the outcome of a hit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum VesselKind {
+    Rocket,
+    SpentStage,
+    Debris,
+}
+
+struct Vessel {
+    entity: Entity,
+    kind: VesselKind,
+    n_body: NewtonBody,
+    reference: ReferenceBody,
+    //in the frame of the body
+    hull: [DVec2; 3],
+}
+
+impl Vessel {
+    fn new(
+        entity: Entity,
+        kind: VesselKind,
+        n_body: &NewtonBody,
+        reference: &ReferenceBody,
+        hull: [DVec2; 3],
+    ) -> Self {
+        Vessel {
+            entity,
+            kind,
+            n_body: *n_body,
+            reference: *reference,
+            hull,
+        }
+    }
+
+    fn outline(&self) -> [DVec2; 3] {
+        self.hull
+            .map(|p| self.n_body.pos + self.n_body.angle.rotate(p))
+    }
+}
+
+//rockets, spent stages and debris whose hulls touch are pushed apart and stop
+//closing in when they meet as gently as a landing, a harder hit damages the
+//rockets or wrecks everything involved, pieces of debris are too small to hit
+//each other and a stage is left out in the step it separates from inside the
+//hull of the rocket
+#[system]
+#[read_component(Rocket)]
+#[read_component(SpentStage)]
+#[read_component(Separating)]
+#[read_component(Debris)]
+#[read_component(ReferenceBody)]
+#[read_component(Crashed)]
+#[write_component(NewtonBody)]
+pub fn vessel_collision(
+    world: &mut SubWorld,
+    command_buffer: &mut CommandBuffer,
+    #[resource] tolerances: &LandingTolerances,
+) {
+    let mut vessels = Vec::new();
+    let mut query =
+        <(Entity, &Rocket, &NewtonBody, &ReferenceBody)>::query().filter(!component::<Crashed>());
+    vessels.extend(query.iter(world).map(|(e, rocket, n_body, reference)| {
+        Vessel::new(*e, VesselKind::Rocket, n_body, reference, rocket.hull())
+    }));
+    let mut query = <(Entity, &SpentStage, &NewtonBody, &ReferenceBody)>::query()
+        .filter(!component::<Separating>());
+    vessels.extend(query.iter(world).map(|(e, stage, n_body, reference)| {
+        Vessel::new(*e, VesselKind::SpentStage, n_body, reference, stage.hull)
+    }));
+    let mut query = <(Entity, &Debris, &NewtonBody, &ReferenceBody)>::query();
+    vessels.extend(query.iter(world).map(|(e, debris, n_body, reference)| {
+        Vessel::new(*e, VesselKind::Debris, n_body, reference, debris.hull)
+    }));
+    for e in <Entity>::query()
+        .filter(component::<Separating>())
+        .iter(world)
+    {
+        command_buffer.remove_component::<Separating>(*e);
+    }
+
+    let mut wrecked = vec![false; vessels.len()];
+    let mut pushed = vec![false; vessels.len()];
+    for i in 0..vessels.len() {
+        for j in i + 1..vessels.len() {
+            let (a, b) = (&vessels[i], &vessels[j]);
+            if wrecked[i]
+                || wrecked[j]
+                || (a.kind == VesselKind::Debris && b.kind == VesselKind::Debris)
+            {
+                continue;
+            }
+            let (a_outline, b_outline) = (a.outline(), b.outline());
+            if !hulls_touch(&a_outline, &b_outline) {
+                continue;
+            }
+            //from `a` towards `b`
+            let (normal, depth) = match triangles_penetration(&a_outline, &b_outline) {
+                Some(penetration) => penetration,
+                None => continue,
+            };
+            let (closing_speed, sliding_speed) = impact_speeds(b.n_body.vel - a.n_body.vel, normal);
+            let outcome = tolerances.classify(closing_speed.max(0.0), sliding_speed, 0.0, 0.0);
+            if outcome == LandingOutcome::Crashed {
+                log::info!(
+                    "{:?} {:?} hit {:?} {:?} at {:.1} m/s",
+                    a.kind,
+                    a.entity,
+                    b.kind,
+                    b.entity,
+                    closing_speed
+                );
+                let mass = a.n_body.mass + b.n_body.mass;
+                let vel = (a.n_body.vel * a.n_body.mass + b.n_body.vel * b.n_body.mass) / mass;
+                for vessel in [a, b] {
+                    wreck(command_buffer, vessel, vel);
+                }
+                wrecked[i] = true;
+                wrecked[j] = true;
+                continue;
+            }
+            if outcome == LandingOutcome::HardLanded {
+                for vessel in [a, b] {
+                    if vessel.kind == VesselKind::Rocket {
+                        command_buffer.add_component(vessel.entity, Damaged);
+                    }
+                }
+            }
+            //out of each other by the mass of the other one, and the closing
+            //speed is lost while the sliding is kept
+            let a_share = a.n_body.mass / (a.n_body.mass + b.n_body.mass);
+            let closing = normal * closing_speed.max(0.0);
+            vessels[i].n_body.pos -= normal * depth * (1.0 - a_share);
+            vessels[i].n_body.vel -= closing * (1.0 - a_share);
+            vessels[j].n_body.pos += normal * depth * a_share;
+            vessels[j].n_body.vel += closing * a_share;
+            pushed[i] = true;
+            pushed[j] = true;
+        }
+    }
+
+    for (i, vessel) in vessels.iter().enumerate() {
+        if !pushed[i] || wrecked[i] {
+            continue;
+        }
+        if let Ok(mut entry) = world.entry_mut(vessel.entity) {
+            let n_body = entry.get_component_mut::<NewtonBody>().unwrap();
+            n_body.pos = vessel.n_body.pos;
+            n_body.vel = vessel.n_body.vel;
+        }
+    }
+}
+
+//rockets are left as wrecks, stages break up and debris is gone, `vel` is
+//the common velocity of the hit
+fn wreck(command_buffer: &mut CommandBuffer, vessel: &Vessel, vel: DVec2) {
+    let bounce = (vessel.n_body.vel - vel) * DEBRIS_RESTITUTION;
+    match vessel.kind {
+        VesselKind::Rocket => command_buffer.add_component(vessel.entity, Crashed),
+        VesselKind::SpentStage => command_buffer.remove(vessel.entity),
+        VesselKind::Debris => {
+            command_buffer.remove(vessel.entity);
+            return;
+        }
+    }
+    spawn_debris(
+        command_buffer,
+        &vessel.n_body,
+        &vessel.hull,
+        &vessel.reference,
+        vessel.n_body.pos,
+        vel,
+        bounce,
+    );
+}
+
+//broad phase against a circle around the whole outline, then the hull edges
+//against the ground below the hull, which is close to a circle through its
+//highest point
+fn hull_touches_surface(hull: &[DVec2; 3], c_body: &CelestialBody, planet: &NewtonBody) -> bool {
+    let (hull_lt, hull_rb) = triangle_aabb(hull[0], hull[1], hull[2]);
+    let (planet_lt, planet_rb) = circle_aabb(planet.pos, c_body.max_surface_radius());
+    if !is_aabb_intersected(hull_lt, hull_rb, planet_lt, planet_rb) {
+        return false;
+    }
+    let ground = hull
+        .iter()
+        .map(|p| c_body.surface_radius(planet.to_local(*p - planet.pos).normalize_or_zero()))
+        .fold(0.0, f64::max);
+    (0..3).any(|i| is_segment_intersects_circle(hull[i], hull[(i + 1) % 3], planet.pos, ground))
+}
+
+//broad phase on the bounding boxes, then the triangles themselves
+fn hulls_touch(a: &[DVec2; 3], b: &[DVec2; 3]) -> bool {
+    let (a_lt, a_rb) = triangle_aabb(a[0], a[1], a[2]);
+    let (b_lt, b_rb) = triangle_aabb(b[0], b[1], b[2]);
+    is_aabb_intersected(a_lt, a_rb, b_lt, b_rb) && is_triangles_intersected(a, b)
+}
+
+//the wreck of the vessel with `hull` scattered around `bounce` on top of `vel`
+fn spawn_debris(
+    command_buffer: &mut CommandBuffer,
+    n_body: &NewtonBody,
+    hull: &[DVec2; 3],
+    reference: &ReferenceBody,
+    pos: DVec2,
+    vel: DVec2,
+    bounce: DVec2,
+) {
+    let mut rng = rand::rng();
+    //of the same total area
+    let hull = hull.map(|p| p / (DEBRIS_PIECES as f64).sqrt());
+    for _ in 0..DEBRIS_PIECES {
+        let spread = DVec2::from_angle(rng.random_range(-DEBRIS_SPREAD..=DEBRIS_SPREAD));
+        command_buffer.push((
+            Debris { hull },
+            NewtonBody {
+                mass: n_body.mass / DEBRIS_PIECES as f64,
+                inertia: n_body.inertia / DEBRIS_PIECES as f64,
+                pos,
+                vel: vel + spread.rotate(bounce),
+                prev_pos: pos,
+                angular_vel: rng.random_range(-1.0..=1.0),
+                force: DVec2::ZERO,
+                torque: 0.0,
+                ..*n_body
+            },
+            TestParticle,
+            *reference,
+        ));
+    }
+}
+
+#[system]
+pub fn log_touchdowns(#[resource] events: &mut Events<Touchdown>) {
+    for touchdown in events.drain() {
+        log::info!(
+            "{:?} touched down on {:?} at {:.0} s: {:?}, vertical {:.1} m/s, horizontal {:.1} m/s, tilt {:.1} deg, slope {:.1} deg",
+            touchdown.entity,
+            touchdown.planet,
+            touchdown.time,
+            touchdown.outcome,
+            touchdown.vertical_speed,
+            touchdown.horizontal_speed,
+            touchdown.tilt.to_degrees(),
+            touchdown.slope.to_degrees()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use super::*;
+    use crate::{
+        assembly::load_rocket, color::Color, components::celestial_body::CelestialBodyType,
+    };
+
+    const RADIUS: f64 = 1e6;
+
+    #[test]
+    fn classify_boundaries() {
+        let t = LandingTolerances::default();
+        let h = HARD_LANDING_FACTOR;
+        let cases = [
+            ((t.vertical_speed, 0.0, 0.0, 0.0), LandingOutcome::Landed),
+            (
+                (t.vertical_speed * 1.01, 0.0, 0.0, 0.0),
+                LandingOutcome::HardLanded,
+            ),
+            (
+                (t.vertical_speed * h, 0.0, 0.0, 0.0),
+                LandingOutcome::HardLanded,
+            ),
+            (
+                (t.vertical_speed * h * 1.01, 0.0, 0.0, 0.0),
+                LandingOutcome::Crashed,
+            ),
+            ((0.0, t.horizontal_speed, 0.0, 0.0), LandingOutcome::Landed),
+            (
+                (0.0, t.horizontal_speed * 1.01, 0.0, 0.0),
+                LandingOutcome::HardLanded,
+            ),
+            (
+                (0.0, t.horizontal_speed * h, 0.0, 0.0),
+                LandingOutcome::HardLanded,
+            ),
+            (
+                (0.0, t.horizontal_speed * h * 1.01, 0.0, 0.0),
+                LandingOutcome::Crashed,
+            ),
+            ((0.0, 0.0, t.tilt, 0.0), LandingOutcome::Landed),
+            ((0.0, 0.0, t.tilt * 1.01, 0.0), LandingOutcome::HardLanded),
+            ((0.0, 0.0, t.tilt * h, 0.0), LandingOutcome::HardLanded),
+            ((0.0, 0.0, t.tilt * h * 1.01, 0.0), LandingOutcome::Crashed),
+            ((0.0, 0.0, 0.0, t.slope), LandingOutcome::Landed),
+            ((0.0, 0.0, 0.0, t.slope * 1.01), LandingOutcome::HardLanded),
+            ((0.0, 0.0, 0.0, t.slope * h), LandingOutcome::HardLanded),
+            ((0.0, 0.0, 0.0, t.slope * h * 1.01), LandingOutcome::Crashed),
+            //the worst ratio decides, all at tolerance is still a landing
+            (
+                (t.vertical_speed, t.horizontal_speed, t.tilt, t.slope),
+                LandingOutcome::Landed,
+            ),
+            (
+                (t.vertical_speed, t.horizontal_speed * h * 1.01, 0.0, 0.0),
+                LandingOutcome::Crashed,
+            ),
+        ];
+        for ((vertical, horizontal, tilt, slope), outcome) in cases.iter() {
+            assert_eq!(
+                t.classify(*vertical, *horizontal, *tilt, *slope),
+                *outcome,
+                "vertical {} horizontal {} tilt {} slope {}",
+                vertical,
+                horizontal,
+                tilt,
+                slope
+            );
+        }
+    }
+
+    #[test]
+    fn impact_speeds_follow_the_slope() {
+        let falling = dvec2(2.0, -5.0);
+        assert_eq!(impact_speeds(falling, DVec2::Y), (5.0, 2.0));
+
+        //straight down onto a slope is partly along the ground
+        let slope = 30f64.to_radians();
+        let normal = DVec2::from_angle(slope).rotate(DVec2::Y);
+        let (vertical, horizontal) = impact_speeds(dvec2(0.0, -5.0), normal);
+        assert!((vertical - 5.0 * slope.cos()).abs() < 1e-12);
+        assert!((horizontal - 5.0 * slope.sin()).abs() < 1e-12);
+
+        //sliding down the slope does not hit it
+        let (vertical, horizontal) = impact_speeds(normal.perp() * 4.0, normal);
+        assert!(vertical.abs() < 1e-12);
+        assert!((horizontal - 4.0).abs() < 1e-12);
+    }
+
+    //a rocket with its nose up crossing the ground of a round planet at the
+    //origin during the step
+    fn crossing_world(vel: DVec2) -> (World, Entity) {
+        let mut world = World::default();
+        let planet_body = NewtonBody {
+            angle: DVec2::X,
+            angular_vel: 0.0,
+            inertia: 1.0,
+            mass: 1e22,
+            pos: DVec2::ZERO,
+            vel: DVec2::ZERO,
+            acc: DVec2::ZERO,
+            force: DVec2::ZERO,
+            torque: 0.0,
+            prev_pos: DVec2::ZERO,
+            prev_angle: DVec2::X,
+        };
+        let planet = world.push((
+            CelestialBody {
+                b_type: CelestialBodyType::Planet,
+                radius: RADIUS,
+                color: Color::GREEN,
+                atmosphere: None,
+                terrain: None,
+            },
+            planet_body,
+        ));
+        let rocket = load_rocket("hopper").unwrap();
+        let rocket_body = NewtonBody {
+            angle: DVec2::NEG_X,
+            inertia: rocket.inertia(),
+            mass: rocket.mass(),
+            pos: dvec2(0.0, RADIUS - 10.0),
+            vel,
+            prev_pos: dvec2(0.0, RADIUS + 10.0),
+            prev_angle: DVec2::NEG_X,
+            ..planet_body
+        };
+        let e = world.push((
+            rocket,
+            rocket_body,
+            ReferenceBody {
+                id: planet,
+                closest_surface_point: DVec2::ZERO,
+                closest_surface_point_a: DVec2::ZERO,
+            },
+        ));
+        (world, e)
+    }
+
+    fn run_landing(world: &mut World) -> Vec<Touchdown> {
+        let mut resources = Resources::default();
+        resources.insert(LandingTolerances::default());
+        resources.insert(Events::<Touchdown>::new());
+        resources.insert(PhysicsClock::new(0.1, 1));
+        resources.insert(Dt(0.1));
+        let mut schedule = Schedule::builder().add_system(landing_system()).build();
+        schedule.execute(world, &mut resources);
+        let mut events = resources.get_mut::<Events<Touchdown>>().unwrap();
+        events.drain().collect()
+    }
+
+    #[test]
+    fn touchdown_is_sent_at_the_point_of_impact() {
+        let (mut world, e) = crossing_world(dvec2(1.0, -5.0));
+        let touchdowns = run_landing(&mut world);
+
+        assert_eq!(touchdowns.len(), 1);
+        let touchdown = touchdowns[0];
+        assert_eq!(touchdown.entity, e);
+        assert_eq!(touchdown.outcome, LandingOutcome::Landed);
+        assert!((touchdown.vertical_speed - 5.0).abs() < 1e-9);
+        assert!((touchdown.horizontal_speed - 1.0).abs() < 1e-9);
+        assert!(touchdown.tilt.abs() < 1e-9);
+        assert!(touchdown.slope.abs() < 1e-9);
+        assert!((touchdown.time - 0.1).abs() < 1e-12);
+
+        let entry = world.entry(e).unwrap();
+        assert!(entry.get_component::<LandingRelation>().is_ok());
+        let pos = entry.get_component::<NewtonBody>().unwrap().pos;
+        assert!((pos.y - RADIUS).abs() < 1e-6);
+    }
+
+    #[test]
+    fn climbing_rocket_is_not_moved() {
+        let (mut world, e) = crossing_world(dvec2(0.0, 5.0));
+        assert!(run_landing(&mut world).is_empty());
+
+        let entry = world.entry(e).unwrap();
+        assert!(entry.get_component::<LandingRelation>().is_err());
+        let pos = entry.get_component::<NewtonBody>().unwrap().pos;
+        assert_eq!(pos, dvec2(0.0, RADIUS - 10.0));
+    }
+
+    //two rockets side by side, overlapping by half their width, and a stage
+    //that has just separated from the first one
+    fn vessels_world(closing_speed: f64) -> (World, [Entity; 3]) {
+        let (mut world, _) = crossing_world(DVec2::ZERO);
+        let planet = *<Entity>::query()
+            .filter(component::<CelestialBody>())
+            .iter(&world)
+            .next()
+            .unwrap();
+        let rocket = load_rocket("hopper").unwrap();
+        let reference = ReferenceBody {
+            id: planet,
+            closest_surface_point: DVec2::ZERO,
+            closest_surface_point_a: DVec2::ZERO,
+        };
+        let body = |pos: DVec2, vel: DVec2| NewtonBody {
+            angle: DVec2::Y,
+            angular_vel: 0.0,
+            inertia: rocket.inertia(),
+            mass: rocket.mass(),
+            pos,
+            vel,
+            acc: DVec2::ZERO,
+            force: DVec2::ZERO,
+            torque: 0.0,
+            prev_pos: pos,
+            prev_angle: DVec2::Y,
+        };
+        let far = dvec2(0.0, 10.0 * RADIUS);
+        let side = dvec2(0.0, rocket.width / 2.0);
+        let a = world.push((rocket.clone(), body(far, DVec2::ZERO), reference));
+        let b = world.push((
+            rocket.clone(),
+            body(far + side, -side.normalize() * closing_speed),
+            reference,
+        ));
+        let stage = world.push((
+            SpentStage {
+                stage: *rocket.stage(),
+                hull: rocket.hull(),
+            },
+            body(far, DVec2::ZERO),
+            reference,
+            Separating,
+        ));
+        (world, [a, b, stage])
+    }
+
+    fn run_vessel_collision(world: &mut World) {
+        let mut resources = Resources::default();
+        resources.insert(LandingTolerances::default());
+        let mut schedule = Schedule::builder()
+            .add_system(vessel_collision_system())
+            .build();
+        schedule.execute(world, &mut resources);
+    }
+
+    #[test]
+    fn gentle_contact_pushes_vessels_apart() {
+        let (mut world, [a, b, stage]) = vessels_world(1.0);
+        run_vessel_collision(&mut world);
+
+        let body = |e| {
+            *world
+                .entry_ref(e)
+                .unwrap()
+                .get_component::<NewtonBody>()
+                .unwrap()
+        };
+        let (a_body, b_body) = (body(a), body(b));
+        let rocket = load_rocket("hopper").unwrap();
+        let outline =
+            |n_body: &NewtonBody| rocket.hull().map(|p| n_body.pos + n_body.angle.rotate(p));
+        //left touching at most
+        let penetration = triangles_penetration(&outline(&a_body), &outline(&b_body));
+        assert!(penetration.is_none_or(|(_, depth)| depth < 1e-6));
+        //the closing speed along the contact normal is lost, the momentum
+        //of the equal masses is kept
+        assert!(a_body.vel.distance(b_body.vel) < 0.5);
+        assert!((a_body.vel + b_body.vel).distance(dvec2(0.0, -1.0)) < 1e-12);
+
+        for e in [a, b] {
+            let entry = world.entry(e).unwrap();
+            assert!(entry.get_component::<Crashed>().is_err());
+            assert!(entry.get_component::<Damaged>().is_err());
+        }
+        //the stage inside the first rocket is left alone, but only once
+        let entry = world.entry(stage).unwrap();
+        assert!(entry.get_component::<Separating>().is_err());
+        assert_eq!(
+            entry.get_component::<NewtonBody>().unwrap().vel,
+            DVec2::ZERO
+        );
+    }
+
+    #[test]
+    fn hard_contact_damages_rockets() {
+        let speed = LandingTolerances::default().vertical_speed * 1.5;
+        let (mut world, [a, b, _]) = vessels_world(speed);
+        run_vessel_collision(&mut world);
+        for e in [a, b] {
+            assert!(world.entry(e).unwrap().get_component::<Damaged>().is_ok());
+        }
+    }
+
+    #[test]
+    fn fast_contact_wrecks_vessels() {
+        let speed = LandingTolerances::default().vertical_speed * 3.0;
+        let (mut world, [a, b, _]) = vessels_world(speed);
+        run_vessel_collision(&mut world);
+        for e in [a, b] {
+            assert!(world.entry(e).unwrap().get_component::<Crashed>().is_ok());
+        }
+        let debris = <&Debris>::query().iter(&world).count();
+        assert_eq!(debris, 2 * DEBRIS_PIECES);
+    }
+
+    #[test]
+    fn separated_stage_collides_after_its_first_step() {
+        let (mut world, [_, b, stage]) = vessels_world(0.0);
+        world.remove(b);
+        run_vessel_collision(&mut world);
+        assert_eq!(
+            world
+                .entry(stage)
+                .unwrap()
+                .get_component::<NewtonBody>()
+                .unwrap()
+                .pos,
+            dvec2(0.0, 10.0 * RADIUS)
+        );
+        run_vessel_collision(&mut world);
+        assert_ne!(
+            world
+                .entry(stage)
+                .unwrap()
+                .get_component::<NewtonBody>()
+                .unwrap()
+                .pos,
+            dvec2(0.0, 10.0 * RADIUS)
+        );
+    }
+}
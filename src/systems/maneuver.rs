@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+
+use legion::{systems::CommandBuffer, world::SubWorld, *};
+
+use crate::{
+    components::{
+        celestial_body::CelestialBody,
+        maneuver::ManeuverNode,
+        newton_body::NewtonBody,
+        reference_body::ReferenceBody,
+        rocket::{PlayerInput, Rocket},
+        sphere_of_influence::SphereOfInfluence,
+        trajectory::Trajectory,
+    },
+    orbit::OrbitalElements,
+    physics_clock::PhysicsClock,
+    systems::trajectory::{predict, reference_orbit},
+    FrameDt,
+};
+
+//fraction of the orbital speed at the node added per second a key is held
+const DELTA_V_RATE: f64 = 0.1;
+//fraction of the orbital period the node moves per second a key is held
+const NODE_TIME_RATE: f64 = 0.1;
+//stands in for the period on open orbits leaving nowhere
+const OPEN_ORBIT_TIME_SCALE: f64 = 3600.0;
+
+//places, edits and removes the maneuver node and plans the burn, the node is
+//gone once its burn is over
+#[system(for_each)]
+#[read_component(NewtonBody)]
+#[read_component(CelestialBody)]
+#[read_component(SphereOfInfluence)]
+#[allow(clippy::too_many_arguments)]
+pub fn maneuver(
+    world: &SubWorld,
+    command_buffer: &mut CommandBuffer,
+    entity: &Entity,
+    rocket: &Rocket,
+    n_body: &NewtonBody,
+    reference: &ReferenceBody,
+    trajectory: &Trajectory,
+    node: Option<&mut ManeuverNode>,
+    #[resource] input: &mut HashSet<PlayerInput>,
+    #[resource] frame_dt: &FrameDt,
+    #[resource] clock: &PhysicsClock,
+) {
+    let toggle = input.remove(&PlayerInput::ToggleManeuver);
+    let r = match reference_orbit(world, n_body, reference.id, clock.time) {
+        Some(r) => r,
+        None => return,
+    };
+    let time_scale = r
+        .orbit
+        .period()
+        .or_else(|| trajectory.soi_exit.map(|exit| exit.time))
+        .unwrap_or(OPEN_ORBIT_TIME_SCALE);
+
+    let node = match node {
+        Some(_) if toggle => {
+            command_buffer.remove_component::<ManeuverNode>(*entity);
+            return;
+        }
+        Some(node) if clock.time > node.burn_end() => {
+            log::debug!("maneuver node at {:.0} s expired", node.time);
+            command_buffer.remove_component::<ManeuverNode>(*entity);
+            return;
+        }
+        Some(node) => node,
+        None => {
+            if toggle {
+                //next apsis, or a bit ahead when the trajectory has none
+                let lead = [trajectory.apoapsis, trajectory.periapsis]
+                    .iter()
+                    .flatten()
+                    .map(|apsis| apsis.time)
+                    .fold(f64::INFINITY, f64::min);
+                let lead = if lead.is_finite() {
+                    lead
+                } else {
+                    NODE_TIME_RATE * time_scale
+                };
+                command_buffer.add_component(*entity, ManeuverNode::new(clock.time + lead));
+            }
+            return;
+        }
+    };
+
+    let dt = frame_dt.0;
+    if input.contains(&PlayerInput::ManeuverLater) {
+        node.time += NODE_TIME_RATE * time_scale * dt;
+    } else if input.contains(&PlayerInput::ManeuverEarlier) {
+        node.time = (node.time - NODE_TIME_RATE * time_scale * dt).max(clock.time);
+    }
+    let (pos, vel) = r.orbit.state_vectors_at(node.time);
+    let delta_v_step = DELTA_V_RATE * vel.length() * dt;
+    if input.contains(&PlayerInput::ManeuverPrograde) {
+        node.prograde += delta_v_step;
+    } else if input.contains(&PlayerInput::ManeuverRetrograde) {
+        node.prograde -= delta_v_step;
+    }
+    if input.contains(&PlayerInput::ManeuverNormal) {
+        node.normal += delta_v_step;
+    } else if input.contains(&PlayerInput::ManeuverAntiNormal) {
+        node.normal -= delta_v_step;
+    }
+    if input.contains(&PlayerInput::ManeuverRadialOut) {
+        node.radial += delta_v_step;
+    } else if input.contains(&PlayerInput::ManeuverRadialIn) {
+        node.radial -= delta_v_step;
+    }
+
+    let delta_v = node.burn_at(pos, vel);
+    node.position = pos;
+    node.burn = delta_v;
+    node.burn_duration = rocket.burn_duration(node.delta_v());
+    node.burn_in = node.burn_start() - clock.time;
+
+    node.trajectory.clear();
+    let after = OrbitalElements::from_state_vectors(pos, vel + delta_v, r.orbit.mu, node.time);
+    if after.semi_latus_rectum > 0.0 {
+        node.trajectory.reference = Some(reference.id);
+        predict(&after, node.time, r.radius, r.soi, &mut node.trajectory);
+    }
+}
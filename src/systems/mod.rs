@@ -0,0 +1,19 @@
+pub mod celestial_body;
+pub mod celestial_collision;
+pub mod conservation;
+pub mod drag;
+pub mod flight_info;
+pub mod landing;
+pub mod maneuver;
+pub mod newton_body;
+pub mod on_rails;
+pub mod performance_info;
+pub mod planet_resting;
+#[cfg(feature = "render")]
+pub mod render;
+pub mod rocket;
+pub mod sas;
+pub mod sphere_of_influence;
+pub mod staging;
+pub mod time_warp;
+pub mod trajectory;
@@ -0,0 +1,208 @@
+use glam::DVec2;
+use legion::{world::SubWorld, *};
+
+use crate::{
+    components::{
+        landing::LandingRelation, newton_body::NewtonBody, on_rails::OnRails,
+        test_particle::TestParticle,
+    },
+    gravity::{potential_energy, Gravity, PointMass, G},
+    integrator::{AdaptiveStepping, Integrator, State},
+    Dt,
+};
+
+#[system(for_each)]
+pub fn begin_step(body: &mut NewtonBody) {
+    body.begin_step();
+}
+
+#[system]
+#[read_component(OnRails)]
+#[read_component(TestParticle)]
+#[write_component(NewtonBody)]
+pub fn celestial_body<I: Integrator>(
+    world: &mut SubWorld,
+    #[resource] dt: &Dt,
+    #[resource] gravity: &Gravity,
+    #[resource] stepping: &AdaptiveStepping,
+) {
+    let mut query = <(&mut NewtonBody, Option<&OnRails>, Option<&TestParticle>)>::query()
+        .filter(!component::<LandingRelation>());
+    let (rails, r): (Vec<_>, Vec<_>) = query
+        .iter_mut(world)
+        .partition(|(_, on_rails, _)| on_rails.is_some());
+    //bodies on rails were already moved to the end of the step, they only attract
+    let rails: Vec<_> = rails
+        .iter()
+        .filter(|(_, _, test_particle)| test_particle.is_none())
+        .map(|(n_body, _, _)| **n_body)
+        .collect();
+    let (mut r, attracts): (Vec<&mut NewtonBody>, Vec<bool>) = r
+        .into_iter()
+        .map(|(n_body, _, test_particle)| (n_body, test_particle.is_none()))
+        .unzip();
+    n_body_iter::<I>(&mut r, &attracts, &rails, dt, gravity, stepping); //&Dt(3600.0)
+}
+
+//`attracts` is false for test particles, they are integrated but excluded from the sources
+fn n_body_iter<I: Integrator>(
+    objs: &mut [&mut NewtonBody],
+    attracts: &[bool],
+    rails: &[NewtonBody],
+    dt: &Dt,
+    gravity: &Gravity,
+    stepping: &AdaptiveStepping,
+) {
+    let dt_f = dt.0;
+    let start: Vec<_> = objs
+        .iter()
+        .map(|o| State {
+            pos: o.pos,
+            vel: o.vel,
+            acc: o.acc,
+        })
+        .collect();
+    let masses: Vec<_> = objs
+        .iter()
+        .zip(attracts)
+        .map(|(o, attracts)| if *attracts { o.mass } else { 0.0 })
+        .collect();
+    //external forces are held constant over the step
+    let external: Vec<_> = objs.iter().map(|o| o.force / o.mass).collect();
+    let levels: Vec<_> = (0..start.len())
+        .map(|i| match stepping.max_level {
+            0 => 0,
+            _ => stepping.level(dt_f, encounter_time_scale(i, &start, &masses, rails)),
+        })
+        .collect();
+
+    let mut states = start.clone();
+    for level in 0..=levels.iter().copied().max().unwrap_or(0) {
+        let group: Vec<_> = (0..start.len()).filter(|i| levels[*i] == level).collect();
+        if group.is_empty() {
+            continue;
+        }
+        let substeps = 1u32 << level;
+        let h = dt_f / substeps as f64;
+        let mut group_states: Vec<_> = group.iter().map(|i| start[*i]).collect();
+        for k in 0..substeps {
+            let t0 = k as f64 * h;
+            I::step(&mut group_states, h, |t, positions| {
+                let t = t0 + t;
+                //rails positions are known at the end of the step, extrapolate back to `t`
+                let mut sources: Vec<_> = rails
+                    .iter()
+                    .map(|r| PointMass {
+                        pos: r.pos + r.vel * (t - dt_f),
+                        mass: r.mass,
+                    })
+                    .collect();
+                //bodies of other levels are predicted from the start of the step
+                sources.extend(
+                    start
+                        .iter()
+                        .zip(&masses)
+                        .zip(&levels)
+                        .filter(|((_, mass), l)| **l != level && **mass > 0.0)
+                        .map(|((s, mass), _)| PointMass {
+                            pos: s.pos + s.vel * t + 0.5 * s.acc * t * t,
+                            mass: *mass,
+                        }),
+                );
+                sources.extend(
+                    positions
+                        .iter()
+                        .zip(&group)
+                        .filter(|(_, i)| masses[**i] > 0.0)
+                        .map(|(pos, i)| PointMass {
+                            pos: *pos,
+                            mass: masses[*i],
+                        }),
+                );
+                gravity
+                    .accelerations(&sources, positions)
+                    .iter()
+                    .zip(&group)
+                    .map(|(a, i)| *a + external[*i])
+                    .collect()
+            });
+        }
+        for (s, i) in group_states.into_iter().zip(&group) {
+            states[*i] = s;
+        }
+    }
+
+    for (o, s) in objs.iter_mut().zip(states) {
+        o.pos = s.pos;
+        o.vel = s.vel;
+        o.acc = s.acc;
+        o.update_a(dt);
+    }
+}
+
+//shortest free fall or crossing time of body `i` against every attracting body,
+//`masses` are zero for test particles
+fn encounter_time_scale(i: usize, states: &[State], masses: &[f64], rails: &[NewtonBody]) -> f64 {
+    let body = &states[i];
+    states
+        .iter()
+        .zip(masses)
+        .enumerate()
+        .filter(|(j, (_, mass))| *j != i && **mass > 0.0)
+        .map(|(_, (s, mass))| (s.pos, s.vel, *mass))
+        .chain(rails.iter().map(|r| (r.pos, r.vel, r.mass)))
+        .map(|(pos, vel, mass)| {
+            let r = pos.distance(body.pos);
+            let mu = G * (masses[i] + mass);
+            let free_fall = if mu > 0.0 {
+                (r * r * r / mu).sqrt()
+            } else {
+                f64::INFINITY
+            };
+            let v = vel.distance(body.vel);
+            let crossing = if v > 0.0 { r / v } else { f64::INFINITY };
+            free_fall.min(crossing)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+pub fn gravitational_force(body1: &NewtonBody, body2: &NewtonBody) -> DVec2 {
+    let r_vec = body2.pos - body1.pos;
+    let r_sq = r_vec.length_squared();
+    let force_magnitude = G * body1.mass * body2.mass / r_sq;
+    let force_direction = r_vec.normalize();
+
+    force_magnitude * force_direction
+}
+
+pub fn calculate_energy(bodies: &[&NewtonBody], softening: f64) -> f64 {
+    let mut ke = 0.0;
+    let mut pe = 0.0;
+
+    let num_bodies = bodies.len();
+
+    for body in bodies {
+        ke += 0.5 * body.mass * body.vel.length_squared();
+    }
+
+    for i in 0..num_bodies {
+        for j in (i + 1)..num_bodies {
+            let r_vec = bodies[j].pos - bodies[i].pos;
+            let distance = r_vec.length();
+            if distance > f64::EPSILON * 100.0 {
+                pe += potential_energy(bodies[i].mass, bodies[j].mass, distance, softening);
+            }
+        }
+    }
+
+    ke + pe
+}
+
+pub fn calculate_momentum(bodies: &[&NewtonBody]) -> DVec2 {
+    bodies.iter().map(|b| b.mass * b.vel).sum()
+}
+
+//about the origin
+pub fn calculate_angular_momentum(bodies: &[&NewtonBody]) -> f64 {
+    bodies.iter().map(|b| b.mass * b.pos.perp_dot(b.vel)).sum()
+}
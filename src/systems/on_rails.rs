@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use glam::DVec2;
+use legion::{world::SubWorld, *};
+
+use crate::{
+    components::{newton_body::NewtonBody, on_rails::OnRails},
+    physics_clock::PhysicsClock,
+    Dt,
+};
+
+#[system]
+#[read_component(Entity)]
+#[read_component(OnRails)]
+#[write_component(NewtonBody)]
+pub fn on_rails(world: &mut SubWorld, #[resource] clock: &PhysicsClock, #[resource] dt: &Dt) {
+    //rails are evaluated at the end of the step, like integrated bodies
+    let time = clock.time + dt.0;
+    let mut query = <(Entity, &NewtonBody, Option<&OnRails>)>::query();
+    let bodies: HashMap<_, _> = query
+        .iter(world)
+        .map(|(e, n_body, rails)| (*e, (rails.copied(), n_body.pos, n_body.vel)))
+        .collect();
+
+    let mut states = HashMap::new();
+    let mut query = <(Entity, &mut NewtonBody, &OnRails)>::query();
+    for (e, n_body, _) in query.iter_mut(world) {
+        let (pos, vel) = rails_state(*e, &bodies, time, &mut states);
+        n_body.pos = pos;
+        n_body.vel = vel;
+        n_body.update_a(dt);
+    }
+}
+
+//parents are resolved first so nested rails (moons) follow their planet
+fn rails_state(
+    e: Entity,
+    bodies: &HashMap<Entity, (Option<OnRails>, DVec2, DVec2)>,
+    time: f64,
+    states: &mut HashMap<Entity, (DVec2, DVec2)>,
+) -> (DVec2, DVec2) {
+    if let Some(state) = states.get(&e) {
+        return *state;
+    }
+    let (rails, pos, vel) = bodies[&e];
+    let state = match rails {
+        Some(OnRails::Fixed) => (pos, DVec2::ZERO),
+        Some(OnRails::Orbit { parent, elements }) => {
+            let (parent_pos, parent_vel) = rails_state(parent, bodies, time, states);
+            let (r_pos, r_vel) = elements.state_vectors_at(time);
+            (parent_pos + r_pos, parent_vel + r_vel)
+        }
+        None => (pos, vel),
+    };
+    states.insert(e, state);
+    state
+}
@@ -1,4 +1,4 @@
-use crate::{Dt, FrameDuration};
+use crate::{FrameDt, FrameDuration};
 use legion::system;
 use std::time::{Duration, Instant};
 
@@ -12,7 +12,7 @@ pub struct PerformanceInfo {
 
 #[system]
 pub fn update_info(
-    #[resource] dt: &Dt,
+    #[resource] dt: &FrameDt,
     #[resource] ft: &FrameDuration,
     #[resource] info: &mut PerformanceInfo,
 ) {
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use glam::{DMat3, DVec2};
+use legion::{systems::CommandBuffer, world::SubWorld, *};
+
+use crate::{
+    components::{
+        celestial_body::CelestialBody,
+        landing::{Crashed, LandingRelation},
+        newton_body::NewtonBody,
+        rocket::Rocket,
+    },
+    gravity::G,
+    Dt,
+};
+
+#[system]
+#[read_component(CelestialBody)]
+#[read_component(LandingRelation)]
+#[read_component(Rocket)]
+#[write_component(NewtonBody)]
+pub fn planet_resting(world: &mut SubWorld) {
+    let mut query = <(Entity, &LandingRelation, &Rocket)>::query();
+
+    let rr: HashMap<_, _> = query
+        .iter(world)
+        .map(|(rocket_id, planet, rocket)| {
+            let planet_entity = world.entry_ref(planet.planet_id).unwrap();
+            let planet_n_body = planet_entity.get_component::<NewtonBody>().unwrap();
+            let planet_celestial_body = planet_entity.get_component::<CelestialBody>().unwrap();
+            let up = planet_n_body.angle.rotate(planet.angle_position);
+            //the lowest point of the hull stands on the ground
+            let clearance = rocket
+                .hull()
+                .iter()
+                .map(|p| -planet.attitude.rotate(*p).dot(planet.angle_position))
+                .fold(0.0, f64::max);
+            let rocket_pos_update = planet_n_body.pos
+                + up * (planet_celestial_body.surface_radius(planet.angle_position) + clearance);
+            (
+                *rocket_id,
+                (
+                    planet_n_body.angle.rotate(planet.attitude),
+                    rocket_pos_update,
+                ),
+            )
+        })
+        .collect();
+
+    let mut query = <(Entity, &mut NewtonBody, &LandingRelation)>::query();
+
+    for (rocket_id, rocket_n_body, _) in query.iter_mut(world) {
+        let (angle_updated, pos_updated) = rr.get(rocket_id).unwrap();
+        rocket_n_body.angle = *angle_updated;
+        rocket_n_body.pos = *pos_updated;
+    }
+}
+
+//a landed rocket takes off once its thrust outweighs the local gravity, it
+//leaves with the velocity of the ground below it plus what the surplus thrust
+//gave it during the step, so it is already climbing when `landing` looks at
+//it again, both are taken where the rocket stands on the terrain
+#[system]
+#[read_component(LandingRelation)]
+#[read_component(Crashed)]
+#[write_component(NewtonBody)]
+pub fn liftoff(world: &mut SubWorld, command_buffer: &mut CommandBuffer, #[resource] dt: &Dt) {
+    let mut query =
+        <(Entity, &NewtonBody, &LandingRelation)>::query().filter(!component::<Crashed>());
+
+    let launches: Vec<_> = query
+        .iter(world)
+        .filter_map(|(rocket_id, rocket_n_body, planet)| {
+            let planet_entity = world.entry_ref(planet.planet_id).unwrap();
+            let planet_n_body = planet_entity.get_component::<NewtonBody>().unwrap();
+            let radius = rocket_n_body.pos.distance(planet_n_body.pos);
+            let up = planet_n_body.angle.rotate(planet.angle_position);
+            let weight = G * planet_n_body.mass * rocket_n_body.mass / (radius * radius);
+            if rocket_n_body.force.dot(up) <= weight {
+                return None;
+            }
+            let ground =
+                planet_n_body.vel + velocity_direct_2d(radius, planet_n_body.angular_vel, up);
+            let vel = ground + (rocket_n_body.force - up * weight) / rocket_n_body.mass * dt.0;
+            Some((*rocket_id, planet.planet_id, vel, planet_n_body.angular_vel))
+        })
+        .collect();
+
+    for (rocket_id, planet_id, vel, angular_vel) in launches {
+        let mut entry = world.entry_mut(rocket_id).unwrap();
+        let rocket_n_body = entry.get_component_mut::<NewtonBody>().unwrap();
+        rocket_n_body.vel = vel;
+        rocket_n_body.angular_vel = angular_vel;
+        command_buffer.remove_component::<LandingRelation>(rocket_id);
+        log::info!("{:?} lifted off from {:?}", rocket_id, planet_id);
+    }
+}
+
+//velocity of the surface point in the direction `unit_angle` of a body
+//spinning counterclockwise at `signed_omega`
+fn velocity_direct_2d(radius: f64, signed_omega: f64, unit_angle: DVec2) -> DVec2 {
+    let vx = -signed_omega * radius * unit_angle.y;
+    let vy = signed_omega * radius * unit_angle.x;
+    DVec2::new(vx, vy)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use super::*;
+    use crate::{
+        assembly::load_rocket,
+        color::Color,
+        components::{celestial_body::CelestialBodyType, reference_body::ReferenceBody},
+        events::Events,
+        physics_clock::PhysicsClock,
+        systems::landing::{landing_system, LandingTolerances, Touchdown},
+    };
+
+    const RADIUS: f64 = 1e6;
+    const DT: f64 = 0.1;
+
+    //a hopper standing nose up on top of a round planet at the origin, pushing
+    //up with `thrust`
+    fn landed_world(thrust: f64) -> (World, Entity) {
+        let mut world = World::default();
+        let planet_body = NewtonBody {
+            angle: DVec2::X,
+            angular_vel: 0.0,
+            inertia: 1.0,
+            mass: 1e22,
+            pos: DVec2::ZERO,
+            vel: DVec2::ZERO,
+            acc: DVec2::ZERO,
+            force: DVec2::ZERO,
+            torque: 0.0,
+            prev_pos: DVec2::ZERO,
+            prev_angle: DVec2::X,
+        };
+        let planet = world.push((
+            CelestialBody {
+                b_type: CelestialBodyType::Planet,
+                radius: RADIUS,
+                color: Color::GREEN,
+                atmosphere: None,
+                terrain: None,
+            },
+            planet_body,
+        ));
+        let rocket = load_rocket("hopper").unwrap();
+        let rocket_body = NewtonBody {
+            angle: DVec2::NEG_X,
+            inertia: rocket.inertia(),
+            mass: rocket.mass(),
+            pos: dvec2(0.0, RADIUS),
+            force: DVec2::Y * thrust,
+            prev_pos: dvec2(0.0, RADIUS),
+            prev_angle: DVec2::NEG_X,
+            ..planet_body
+        };
+        let e = world.push((
+            rocket,
+            rocket_body,
+            ReferenceBody {
+                id: planet,
+                closest_surface_point: DVec2::ZERO,
+                closest_surface_point_a: DVec2::ZERO,
+            },
+            LandingRelation {
+                planet_id: planet,
+                angle_position: DVec2::Y,
+                attitude: DVec2::NEG_X,
+            },
+        ));
+        (world, e)
+    }
+
+    fn resources() -> Resources {
+        let mut resources = Resources::default();
+        resources.insert(LandingTolerances::default());
+        resources.insert(Events::<Touchdown>::new());
+        resources.insert(PhysicsClock::new(DT, 1));
+        resources.insert(Dt(DT));
+        resources
+    }
+
+    #[test]
+    fn lifted_off_rocket_is_not_landed_again() {
+        let weight = G * 1e22 * load_rocket("hopper").unwrap().mass() / (RADIUS * RADIUS);
+        let (mut world, e) = landed_world(weight * 2.0);
+        let mut resources = resources();
+        let mut schedule = Schedule::builder()
+            .add_system(liftoff_system())
+            .add_system(planet_resting_system())
+            .build();
+        schedule.execute(&mut world, &mut resources);
+
+        let entry = world.entry(e).unwrap();
+        assert!(entry.get_component::<LandingRelation>().is_err());
+        let n_body = *entry.get_component::<NewtonBody>().unwrap();
+        assert!(n_body.vel.y > 0.0);
+
+        //the next step moves it up and `landing` leaves it in flight
+        let mut entry = world.entry(e).unwrap();
+        let n_body = entry.get_component_mut::<NewtonBody>().unwrap();
+        n_body.prev_pos = n_body.pos;
+        n_body.pos += n_body.vel * DT;
+        let mut schedule = Schedule::builder().add_system(landing_system()).build();
+        schedule.execute(&mut world, &mut resources);
+
+        let entry = world.entry(e).unwrap();
+        assert!(entry.get_component::<LandingRelation>().is_err());
+        let mut events = resources.get_mut::<Events<Touchdown>>().unwrap();
+        assert_eq!(events.drain().count(), 0);
+    }
+
+    #[test]
+    fn rocket_too_weak_stays_landed() {
+        let weight = G * 1e22 * load_rocket("hopper").unwrap().mass() / (RADIUS * RADIUS);
+        let (mut world, e) = landed_world(weight * 0.5);
+        let mut schedule = Schedule::builder().add_system(liftoff_system()).build();
+        schedule.execute(&mut world, &mut resources());
+
+        let entry = world.entry(e).unwrap();
+        assert!(entry.get_component::<LandingRelation>().is_ok());
+    }
+}
@@ -0,0 +1,613 @@
+use glam::{dvec2, vec2, DMat3, DVec2, DVec3, I16Vec2};
+use legion::{world::SubWorld, *};
+use sdl2::{
+    gfx::primitives::DrawRenderer,
+    pixels::Color,
+    rect::Rect,
+    render::{Canvas, TextureAccess},
+    video::Window,
+};
+use std::{cmp::Ordering, f64::consts::TAU};
+use uom::si::{
+    f64::Length,
+    length::{kilometer, meter},
+    velocity::meter_per_second,
+};
+
+use crate::{
+    components::{
+        celestial_body::{CelestialBody, TERRAIN_SAMPLES},
+        debris::Debris,
+        flight_info::FlightInfo,
+        landing::{Crashed, Damaged, LandingRelation},
+        maneuver::ManeuverNode,
+        newton_body::NewtonBody,
+        reference_body::ReferenceBody,
+        rocket::Rocket,
+        sas::Sas,
+        spent_stage::SpentStage,
+        trajectory::Trajectory,
+    },
+    fonts::FontRenderer,
+    physics_clock::PhysicsClock,
+    systems::{
+        conservation::ConservationInfo,
+        performance_info::PerformanceInfo,
+        time_warp::{TimeWarp, WarpLimit},
+    },
+    CameraMode, CanvasResources, FrameDuration, FrameTimer, WindowSize, SPACE_SIZE,
+};
+
+const BACKGROUD_COLOR: Color = Color::BLACK;
+const COLOR: Color = Color::CYAN;
+const ATMOSPHERE_COLOR: Color = Color::RGB(40, 70, 120);
+const TRAJECTORY_COLOR: Color = Color::RGB(0, 160, 255);
+const APOAPSIS_COLOR: Color = Color::RGB(80, 120, 255);
+const PERIAPSIS_COLOR: Color = Color::RGB(255, 160, 0);
+const SOI_EXIT_COLOR: Color = Color::MAGENTA;
+const MANEUVER_COLOR: Color = Color::RGB(255, 255, 255);
+const MANEUVER_TRAJECTORY_COLOR: Color = Color::RGB(255, 120, 40);
+const MARKER_SIZE: i16 = 4;
+//length of the rocket on screen, px
+const ROCKET_SIZE: f64 = 43.3013;
+const SPENT_STAGE_COLOR: Color = Color::RGB(140, 140, 140);
+const TARGET_COLOR: Color = Color::RGB(255, 0, 200);
+
+#[system]
+#[read_component(Rocket)]
+#[read_component(CelestialBody)]
+#[read_component(NewtonBody)]
+#[read_component(ReferenceBody)]
+#[read_component(Trajectory)]
+#[read_component(ManeuverNode)]
+#[read_component(SpentStage)]
+#[read_component(Sas)]
+#[read_component(Debris)]
+#[read_component(Crashed)]
+pub fn render(
+    #[resource] canvas_resources: &mut CanvasResources,
+    // #[resource] camera_mode: &CameraMode,
+    #[resource] window_size: &WindowSize,
+    #[resource] clock: &PhysicsClock,
+    world: &SubWorld,
+) {
+    canvas_resources.canvas.set_draw_color(BACKGROUD_COLOR);
+    canvas_resources.canvas.clear();
+
+    let camera_mode = CameraMode::Default;
+    let (tex, padded) = get_space_rect(window_size.0.x, window_size.0.y);
+    let scale = tex.width() as f64 / SPACE_SIZE;
+    let mut position_query = <(
+        &Rocket,
+        &NewtonBody,
+        &ReferenceBody,
+        &Trajectory,
+        Option<&ManeuverNode>,
+    )>::query();
+    let (rocket, body, reference, trajectory, node) = position_query.iter(world).last().unwrap();
+    let crashed = <(&Rocket, &Crashed)>::query().iter(world).next().is_some();
+    let reference_body = world.entry_ref(reference.id).unwrap();
+    let newton_body_comp = reference_body.get_component::<NewtonBody>().unwrap();
+    let srt_mtx = match camera_mode {
+        CameraMode::Default => {
+            let scale_mtx = DMat3::from_diagonal(DVec3::new(scale, scale, 1.0));
+            let rotate_mtx = DMat3::IDENTITY;
+            let transform_mtx = DMat3::IDENTITY;
+            let mtx = scale_mtx * rotate_mtx * transform_mtx;
+            mtx
+        }
+        CameraMode::Landing => {
+            let reference_body = world.entry_ref(reference.id).unwrap();
+            let newton_body_comp = reference_body.get_component::<NewtonBody>().unwrap();
+            let dst = tex.width() as f64 / body.pos.distance(newton_body_comp.pos);
+            let scale_mtx = DMat3::from_scale(DVec2::splat(dst * 0.5));
+            let mid = scale_mtx.transform_point2(body.pos.midpoint(newton_body_comp.pos));
+            let mid2 = body.pos.midpoint(newton_body_comp.pos);
+            let rebase2 = DMat3::from_translation(-mid);
+            let rebase3 = DMat3::from_translation(DVec2::splat(tex.width() as f64 / 2.0));
+            let mtx = rebase3 * rebase2 * scale_mtx;
+            mtx
+        }
+    };
+
+    let mut obj_query = <(&CelestialBody, &NewtonBody)>::query();
+
+    {
+        let mut intermediate_texture = canvas_resources
+            .texture_creator
+            .create_texture(
+                None, // Use default pixel format, or specify one like PixelFormatEnum::RGBA8888
+                TextureAccess::Target,
+                tex.width(),
+                tex.height(),
+            )
+            .unwrap();
+        let _ = canvas_resources
+            .canvas
+            .with_texture_canvas(&mut intermediate_texture, |c| {
+                if !crashed {
+                    render_rocket(
+                        c,
+                        &srt_mtx,
+                        rocket,
+                        body,
+                        newton_body_comp.interpolated_pos(clock.alpha),
+                        clock.alpha,
+                    );
+                }
+                for (_, body) in <(&Debris, &NewtonBody)>::query().iter(world) {
+                    let p = srt_mtx
+                        .transform_point2(body.interpolated_pos(clock.alpha))
+                        .as_i16vec2();
+                    let _ = c.filled_circle(p.x, p.y, 2, SPENT_STAGE_COLOR);
+                }
+                let target = <&Sas>::query()
+                    .iter(world)
+                    .find_map(|sas| sas.target)
+                    .and_then(|t| world.entry_ref(t).ok());
+                if let Some(target) = target {
+                    if let (Ok(c_body), Ok(n_body)) = (
+                        target.get_component::<CelestialBody>(),
+                        target.get_component::<NewtonBody>(),
+                    ) {
+                        let p = srt_mtx
+                            .transform_point2(n_body.interpolated_pos(clock.alpha))
+                            .as_i16vec2();
+                        let r = (c_body.radius * scale) as i16 + 2 * MARKER_SIZE;
+                        let _ = c.circle(p.x, p.y, r, TARGET_COLOR);
+                    }
+                }
+                for (_, body) in <(&SpentStage, &NewtonBody)>::query().iter(world) {
+                    render_spent_stage(c, &srt_mtx, body, clock.alpha);
+                }
+                for (c_body, body) in obj_query.iter(world) {
+                    render_celestial_body(c, &srt_mtx, scale, c_body, body, clock.alpha)
+                }
+                if let Some(trajectory_reference) =
+                    trajectory.reference.and_then(|id| world.entry_ref(id).ok())
+                {
+                    let origin = trajectory_reference
+                        .get_component::<NewtonBody>()
+                        .unwrap()
+                        .interpolated_pos(clock.alpha);
+                    render_trajectory(c, &srt_mtx, trajectory, origin, TRAJECTORY_COLOR);
+                    if let Some(node) = node {
+                        render_trajectory(
+                            c,
+                            &srt_mtx,
+                            &node.trajectory,
+                            origin,
+                            MANEUVER_TRAJECTORY_COLOR,
+                        );
+                        let p = srt_mtx
+                            .transform_point2(origin + node.position)
+                            .as_i16vec2();
+                        let _ = c.circle(p.x, p.y, MARKER_SIZE + 2, MANEUVER_COLOR);
+                    }
+                }
+            });
+
+        let _ = canvas_resources
+            .canvas
+            .copy(&intermediate_texture, None, Some(padded));
+    }
+}
+
+//drawn over the world and presents the frame, runs after `render`
+#[system]
+#[read_component(Rocket)]
+#[read_component(FlightInfo)]
+#[read_component(Trajectory)]
+#[read_component(ManeuverNode)]
+#[read_component(Sas)]
+#[read_component(LandingRelation)]
+#[read_component(Damaged)]
+#[read_component(Crashed)]
+#[allow(clippy::too_many_arguments)]
+pub fn render_hud(
+    #[resource] canvas_resources: &mut CanvasResources,
+    #[resource] font_renderer: &mut FontRenderer<1>,
+    #[resource] fd: &mut FrameDuration,
+    #[resource] window_size: &WindowSize,
+    #[resource] performance_info: &PerformanceInfo,
+    #[resource] ft: &FrameTimer,
+    #[resource] time_warp: &TimeWarp,
+    #[resource] conservation: &ConservationInfo,
+    world: &SubWorld,
+) {
+    let mut query = <(
+        &Rocket,
+        &FlightInfo,
+        &Trajectory,
+        Option<&ManeuverNode>,
+        Option<&Sas>,
+    )>::query();
+    let (rocket, flight_info, trajectory, node, sas) = query.iter(world).last().unwrap();
+    let mut query = <(
+        &Rocket,
+        Option<&LandingRelation>,
+        Option<&Damaged>,
+        Option<&Crashed>,
+    )>::query();
+    let status = match query.iter(world).last().unwrap() {
+        (_, _, _, Some(_)) => "CRASHED\nPRESS R TO REVERT",
+        (_, Some(_), Some(_), _) => "LANDED, DAMAGED",
+        (_, Some(_), None, _) => "LANDED",
+        (_, None, Some(_), _) => "IN FLIGHT, DAMAGED",
+        (_, None, None, _) => "IN FLIGHT",
+    };
+
+    render_ui(
+        canvas_resources,
+        window_size,
+        font_renderer,
+        &flight_text(status, rocket, flight_info, trajectory, node, sas),
+        performance_info,
+        time_warp,
+        conservation,
+    );
+
+    fd.0 = ft.0.elapsed();
+    canvas_resources.canvas.present();
+}
+
+fn render_rocket(
+    canvas: &mut Canvas<Window>,
+    srt_mtx: &DMat3,
+    rocket: &Rocket,
+    n_body: &NewtonBody,
+    p: DVec2,
+    alpha: f64,
+) {
+    #[inline]
+    fn tranaslate(x: &DVec2, a: DVec2, pos: DVec2) -> I16Vec2 {
+        (x.rotate(a) + pos).as_i16vec2()
+    }
+
+    let n_body_applied = srt_mtx.transform_point2(n_body.interpolated_pos(alpha));
+    let angle = n_body.interpolated_angle(alpha);
+    //the hull would be invisible at scale, it keeps its proportions on screen
+    let size = ROCKET_SIZE / rocket.length;
+    let poits: Vec<_> = rocket
+        .hull()
+        .iter()
+        .map(|p| tranaslate(&(*p * size), angle, n_body_applied))
+        .collect();
+
+    let _ = canvas.filled_trigon(
+        poits[0].x, poits[0].y, poits[1].x, poits[1].y, poits[2].x, poits[2].y, COLOR,
+    );
+    let _ = canvas.line(poits[2].x, poits[2].y, poits[0].x, poits[0].y, Color::RED);
+    let pp = srt_mtx.transform_point2(p).as_i16vec2();
+    let rr = n_body_applied.as_i16vec2();
+    let _ = canvas.line(rr.x, rr.y, pp.x, pp.y, Color::MAGENTA);
+}
+
+fn render_spent_stage(
+    canvas: &mut Canvas<Window>,
+    srt_mtx: &DMat3,
+    n_body: &NewtonBody,
+    alpha: f64,
+) {
+    let pos = srt_mtx.transform_point2(n_body.interpolated_pos(alpha));
+    let angle = n_body.interpolated_angle(alpha);
+    let poits: Vec<_> = [dvec2(-15.0, 0.0), dvec2(15.0, 0.0), dvec2(0.0, 20.0)]
+        .iter()
+        .map(|p| (p.rotate(angle) + pos).as_i16vec2())
+        .collect();
+    let _ = canvas.trigon(
+        poits[0].x,
+        poits[0].y,
+        poits[1].x,
+        poits[1].y,
+        poits[2].x,
+        poits[2].y,
+        SPENT_STAGE_COLOR,
+    );
+}
+
+fn render_trajectory(
+    canvas: &mut Canvas<Window>,
+    srt_mtx: &DMat3,
+    trajectory: &Trajectory,
+    origin: DVec2,
+    color: Color,
+) {
+    let to_screen = |p: DVec2| srt_mtx.transform_point2(origin + p).as_i16vec2();
+    for segment in trajectory.points.windows(2) {
+        let (a, b) = (to_screen(segment[0]), to_screen(segment[1]));
+        let _ = canvas.line(a.x, a.y, b.x, b.y, color);
+    }
+    for (marker, color) in [
+        (trajectory.apoapsis, APOAPSIS_COLOR),
+        (trajectory.periapsis, PERIAPSIS_COLOR),
+        (trajectory.soi_exit, SOI_EXIT_COLOR),
+    ] {
+        if let Some(marker) = marker {
+            let p = to_screen(marker.pos);
+            let _ = canvas.filled_circle(p.x, p.y, MARKER_SIZE, color);
+        }
+    }
+    if let Some(impact) = trajectory.impact {
+        let p = to_screen(impact.pos);
+        let _ = canvas.line(
+            p.x - MARKER_SIZE,
+            p.y - MARKER_SIZE,
+            p.x + MARKER_SIZE,
+            p.y + MARKER_SIZE,
+            Color::RED,
+        );
+        let _ = canvas.line(
+            p.x - MARKER_SIZE,
+            p.y + MARKER_SIZE,
+            p.x + MARKER_SIZE,
+            p.y - MARKER_SIZE,
+            Color::RED,
+        );
+    }
+}
+
+fn render_celestial_body(
+    canvas: &mut Canvas<Window>,
+    srt_mtx: &DMat3,
+    scale: f64,
+    c_body: &CelestialBody,
+    n_body: &NewtonBody,
+    alpha: f64,
+) {
+    let n_body_applied = srt_mtx
+        .transform_point2(n_body.interpolated_pos(alpha))
+        .as_i16vec2();
+    let radius_applied = c_body.radius * scale;
+    let color = Color::from(c_body.color);
+    let pointer = DVec2::ZERO
+        .with_y(radius_applied)
+        .rotate(n_body.interpolated_angle(alpha))
+        .as_i16vec2()
+        + n_body_applied;
+    match &c_body.terrain {
+        Some(terrain) => {
+            //about one outline point every two pixels of the circumference
+            let points = ((TAU * radius_applied / 2.0) as usize).clamp(16, TERRAIN_SAMPLES);
+            let stride = TERRAIN_SAMPLES / points;
+            let pos = n_body.interpolated_pos(alpha);
+            let angle = n_body.interpolated_angle(alpha);
+            let (xs, ys): (Vec<_>, Vec<_>) = (0..TERRAIN_SAMPLES)
+                .step_by(stride)
+                .map(|i| {
+                    let p = srt_mtx
+                        .transform_point2(pos + angle.rotate(c_body.outline_point(terrain, i)))
+                        .as_i16vec2();
+                    (p.x, p.y)
+                })
+                .unzip();
+            let _ = canvas.polygon(&xs, &ys, color);
+        }
+        None => {
+            let _ = canvas.circle(
+                n_body_applied.x,
+                n_body_applied.y,
+                radius_applied as i16,
+                color,
+            );
+        }
+    }
+    let _ = canvas.line(
+        n_body_applied.x,
+        n_body_applied.y,
+        pointer.x,
+        pointer.y,
+        color,
+    );
+    if let Some(atmosphere) = c_body.atmosphere {
+        let _ = canvas.circle(
+            n_body_applied.x,
+            n_body_applied.y,
+            ((c_body.radius + atmosphere.cutoff_altitude) * scale) as i16,
+            ATMOSPHERE_COLOR,
+        );
+    }
+}
+
+fn render_ui(
+    canvas_resources: &mut CanvasResources,
+    window_size: &WindowSize,
+    font_renderer: &mut FontRenderer<1>,
+    flight_text: &str,
+    performance_info: &PerformanceInfo,
+    time_warp: &TimeWarp,
+    conservation: &ConservationInfo,
+) {
+    font_renderer
+        .render_text(
+            canvas_resources,
+            flight_text,
+            vec2((window_size.0.x - 450) as f32, 0.0),
+            16.0,
+            Color::YELLOW,
+            0,
+        )
+        .unwrap();
+
+    font_renderer
+        .render_text(
+            canvas_resources,
+            &format!(
+                "FPS {} \nF. TIME {} uS",
+                performance_info.fps, performance_info.frame_time
+            ),
+            vec2(0.0, 0.0),
+            16.0,
+            Color::YELLOW,
+            0,
+        )
+        .unwrap();
+
+    if let (true, Some(current)) = (conservation.visible, conservation.current) {
+        font_renderer
+            .render_text(
+                canvas_resources,
+                &format!(
+                    "ENERGY {:.6e} J\n  DRIFT {:.3e}\nMOMENTUM {:.6e} kg m/s\n  DRIFT {:.3e}\nANG. MOMENTUM {:.6e} kg m2/s\n  DRIFT {:.3e}",
+                    current.energy,
+                    conservation.energy_drift,
+                    current.momentum.length(),
+                    conservation.momentum_drift,
+                    current.angular_momentum,
+                    conservation.angular_momentum_drift
+                ),
+                vec2(0.0, 48.0),
+                16.0,
+                Color::YELLOW,
+                0,
+            )
+            .unwrap();
+    }
+
+    let limit = match time_warp.limit {
+        WarpLimit::None => String::new(),
+        WarpLimit::Thrust => format!("\nMAX {}x THRUST", time_warp.max_factor()),
+        WarpLimit::Altitude => format!("\nMAX {}x ALTITUDE", time_warp.max_factor()),
+        WarpLimit::SoiBoundary => format!("\nMAX {}x SOI", time_warp.max_factor()),
+    };
+    font_renderer
+        .render_text(
+            canvas_resources,
+            &format!("WARP {}x{}", time_warp.factor(), limit),
+            vec2((window_size.0.x / 2 - 60) as f32, 0.0),
+            16.0,
+            Color::YELLOW,
+            0,
+        )
+        .unwrap();
+}
+
+fn flight_text(
+    status: &str,
+    rocket: &Rocket,
+    flight_info: &FlightInfo,
+    trajectory: &Trajectory,
+    node: Option<&ManeuverNode>,
+    sas: Option<&Sas>,
+) -> String {
+    format!(
+        "SPEED       {:.1}\nDISTANCE {:.1}\n{}{}{}{}{}{}",
+        flight_info
+            .delta
+            .into_format_args(meter_per_second, uom::fmt::DisplayStyle::Abbreviation),
+        flight_info
+            .distance
+            .into_format_args(meter, uom::fmt::DisplayStyle::Abbreviation),
+        status,
+        fuel_info(rocket),
+        sas.map(sas_info).unwrap_or_default(),
+        orbit_info(flight_info),
+        trajectory_info(trajectory),
+        node.map(maneuver_info).unwrap_or_default()
+    )
+}
+
+fn fuel_info(rocket: &Rocket) -> String {
+    let tank = rocket.stage().tank;
+    //a stage without a tank shows as empty
+    let percent = if tank.capacity > 0.0 {
+        100.0 * tank.fuel / tank.capacity
+    } else {
+        0.0
+    };
+    format!(
+        "\nSTAGE {}\nFUEL {:.1} t ({:.0}%)\nDELTA-V {:.0} m/s",
+        rocket.stages.len(),
+        tank.fuel / 1000.0,
+        percent,
+        rocket.delta_v()
+    )
+}
+
+fn sas_info(sas: &Sas) -> String {
+    let mode = sas.mode.map_or("OFF", |mode| mode.name());
+    let target = if sas.target.is_some() {
+        "\nTARGET SET"
+    } else {
+        ""
+    };
+    format!("\nSAS {}{}", mode, target)
+}
+
+fn maneuver_info(node: &ManeuverNode) -> String {
+    let burn_time = if node.burn_duration.is_finite() {
+        format!("{:.1} s", node.burn_duration)
+    } else {
+        "-".to_string()
+    };
+    format!(
+        "\nNODE DV {:.1} m/s\nPROGRADE {:.1} NORMAL {:.1} RADIAL {:.1}\nBURN IN {:.0} s\nBURN TIME {}",
+        node.delta_v(),
+        node.prograde,
+        node.normal,
+        node.radial,
+        node.burn_in,
+        burn_time
+    )
+}
+
+fn trajectory_info(trajectory: &Trajectory) -> String {
+    [
+        ("TIME TO AP", trajectory.apoapsis),
+        ("TIME TO PE", trajectory.periapsis),
+        ("IMPACT IN", trajectory.impact),
+        ("SOI EXIT IN", trajectory.soi_exit),
+    ]
+    .iter()
+    .filter_map(|(label, marker)| marker.map(|m| format!("\n{} {:.0} s", label, m.time)))
+    .collect()
+}
+
+fn orbit_info(flight_info: &FlightInfo) -> String {
+    let orbit = match flight_info.orbit {
+        Some(orbit) => orbit,
+        None => return String::new(),
+    };
+    let format_length = |length: Option<Length>| match length {
+        Some(l) => format!(
+            "{:.1}",
+            l.into_format_args(kilometer, uom::fmt::DisplayStyle::Abbreviation)
+        ),
+        None => "-".to_string(),
+    };
+    let period = match orbit.period() {
+        Some(period) => format!("{:.0} s", period),
+        None => "-".to_string(),
+    };
+    format!(
+        "\nAPOAPSIS {}\nPERIAPSIS {}\nECCENTRICITY {:.3}\nPERIOD {}",
+        format_length(flight_info.apoapsis),
+        format_length(flight_info.periapsis),
+        orbit.eccentricity,
+        period
+    )
+}
+
+fn get_space_rect(x: i32, y: i32) -> (Rect, Rect) {
+    #[inline]
+    fn padding(a: i32, b: i32) -> i32 {
+        (((a - b) as f64) * 0.5) as i32
+    }
+    match x.cmp(&y) {
+        Ordering::Greater => {
+            let r = y as u32;
+            let rr = Rect::new(0, 0, r, r);
+            (rr, rr.right_shifted(padding(x, y)))
+        }
+        Ordering::Less => {
+            let r = x as u32;
+            let rr = Rect::new(0, 0, r, r);
+            (rr, rr.bottom_shifted(padding(y, x)))
+        }
+        _ => {
+            let r = x as u32;
+            let rr = Rect::new(0, 0, r, r);
+            (rr, rr)
+        }
+    }
+}
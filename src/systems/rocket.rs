@@ -0,0 +1,77 @@
+use std::collections::HashSet;
+
+use legion::*;
+
+use crate::{
+    components::{
+        landing::Crashed,
+        newton_body::NewtonBody,
+        rocket::{PlayerInput, Rocket},
+        sas::Sas,
+    },
+    Dt,
+};
+
+#[system(for_each)]
+#[filter(!component::<Crashed>())]
+#[write_component(NewtonBody)]
+#[read_component(Rocket)]
+pub fn update_positions(
+    rocket: &mut Rocket,
+    body: &mut NewtonBody,
+    sas: Option<&Sas>,
+    #[resource] dt: &Dt,
+    #[resource] input: &HashSet<PlayerInput>,
+) {
+    let rotation = if input.contains(&PlayerInput::RotateRight) {
+        1.0
+    } else if input.contains(&PlayerInput::RotateLeft) {
+        -1.0
+    } else {
+        sas.map_or(0.0, |sas| sas.command)
+    };
+    let wheels = rotation * rocket.reaction_wheel_torque();
+
+    let stage = rocket.stage_mut();
+    if input.contains(&PlayerInput::MoveRight) {
+        stage.engine_left.full();
+        stage.engine_right.disable();
+    } else {
+        stage.engine_left.disable();
+    }
+
+    if input.contains(&PlayerInput::MoveLeft) {
+        stage.engine_right.full();
+        stage.engine_left.disable();
+    } else {
+        stage.engine_right.disable();
+    }
+
+    if input.contains(&PlayerInput::MoveForward) {
+        stage.engine_averse.change_throttle(dt.0);
+    }
+
+    if input.contains(&PlayerInput::MoveBackward) {
+        if stage.engine_averse.throttle != 0.0 {
+            stage.engine_averse.change_throttle(-dt.0);
+        } else {
+            stage.engine_reverse.full();
+        }
+    } else {
+        stage.engine_reverse.disable();
+    }
+
+    //engines run for the part of the step the fuel lasts
+    let needed = rocket.mass_flow() * dt.0;
+    let burning = if needed > 0.0 {
+        rocket.stage_mut().tank.drain(needed) / needed
+    } else {
+        0.0
+    };
+    body.mass = rocket.mass();
+    body.inertia = rocket.inertia();
+    let d_f_local = rocket.trust() * burning;
+    let d_f_global = (body.angle).rotate(d_f_local);
+    body.force += d_f_global;
+    body.torque += wheels + rocket.stage().torque() * burning;
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+
+use glam::DVec2;
+use legion::{world::SubWorld, *};
+
+use crate::{
+    components::{
+        celestial_body::CelestialBody,
+        maneuver::ManeuverNode,
+        newton_body::NewtonBody,
+        reference_body::ReferenceBody,
+        rocket::{PlayerInput, Rocket},
+        sas::{Sas, SasMode},
+    },
+    Dt,
+};
+
+//share of the wheel authority planned for braking, the rest absorbs the error
+//of the discrete steps so the nose does not overshoot
+const BRAKING_SHARE: f64 = 0.5;
+
+//switches modes and targets and steers the reaction wheels, manual rotation
+//input takes precedence in `update_positions`
+#[system(for_each)]
+#[read_component(Entity)]
+#[read_component(NewtonBody)]
+#[read_component(CelestialBody)]
+#[allow(clippy::too_many_arguments)]
+pub fn sas(
+    world: &SubWorld,
+    rocket: &Rocket,
+    n_body: &NewtonBody,
+    reference: &ReferenceBody,
+    node: Option<&ManeuverNode>,
+    sas: &mut Sas,
+    #[resource] input: &mut HashSet<PlayerInput>,
+    #[resource] dt: &Dt,
+) {
+    if input.remove(&PlayerInput::ToggleSas) {
+        sas.mode = match sas.mode {
+            Some(_) => None,
+            None => Some(SasMode::HoldHeading),
+        };
+        sas.heading = nose(n_body).to_angle();
+    }
+    for mode in SAS_MODES {
+        if input.remove(&PlayerInput::SelectSas(mode)) {
+            sas.mode = Some(mode);
+            sas.heading = nose(n_body).to_angle();
+        }
+    }
+    if input.remove(&PlayerInput::CycleTarget) {
+        sas.target = next_target(world, n_body, reference.id, sas.target);
+    }
+
+    let mode = match sas.mode {
+        Some(mode) => mode,
+        None => {
+            sas.command = 0.0;
+            return;
+        }
+    };
+    let planet = world
+        .entry_ref(reference.id)
+        .ok()
+        .and_then(|e| e.get_component::<NewtonBody>().ok().copied());
+    let direction = match (mode, planet) {
+        (SasMode::KillRotation, _) => None,
+        (SasMode::HoldHeading, _) => Some(DVec2::from_angle(sas.heading)),
+        (SasMode::Prograde, Some(planet)) => Some(n_body.vel - planet.vel),
+        (SasMode::Retrograde, Some(planet)) => Some(planet.vel - n_body.vel),
+        (SasMode::RadialOut, Some(planet)) => Some(n_body.pos - planet.pos),
+        (SasMode::RadialIn, Some(planet)) => Some(planet.pos - n_body.pos),
+        (SasMode::Target, _) => sas
+            .target
+            .and_then(|t| world.entry_ref(t).ok())
+            .and_then(|e| {
+                e.get_component::<NewtonBody>()
+                    .ok()
+                    .map(|t| t.pos - n_body.pos)
+            }),
+        (SasMode::Maneuver, _) => node.map(|node| node.burn_direction()),
+        _ => None,
+    };
+    //without a direction the rotation is still damped
+    let direction = direction
+        .filter(|d| *d != DVec2::ZERO)
+        .map(|d| d.normalize());
+    sas.command = steer(n_body, direction, rocket.reaction_wheel_torque(), dt.0);
+}
+
+const SAS_MODES: [SasMode; 8] = [
+    SasMode::KillRotation,
+    SasMode::HoldHeading,
+    SasMode::Prograde,
+    SasMode::Retrograde,
+    SasMode::RadialOut,
+    SasMode::RadialIn,
+    SasMode::Target,
+    SasMode::Maneuver,
+];
+
+//the main engine pushes along the nose
+fn nose(n_body: &NewtonBody) -> DVec2 {
+    n_body.angle.rotate(DVec2::NEG_Y)
+}
+
+//angular velocity that can still be stopped at the target direction, the
+//wheel input is whatever reaches it within the step
+fn steer(n_body: &NewtonBody, direction: Option<DVec2>, max_torque: f64, dt: f64) -> f64 {
+    if max_torque <= 0.0 || n_body.inertia <= 0.0 {
+        return 0.0;
+    }
+    let max_angular_acc = max_torque / n_body.inertia;
+    let desired = match direction {
+        Some(direction) => {
+            let error = nose(n_body).angle_to(direction);
+            error.signum() * (2.0 * BRAKING_SHARE * max_angular_acc * error.abs()).sqrt()
+        }
+        None => 0.0,
+    };
+    ((desired - n_body.angular_vel) / (max_angular_acc * dt)).clamp(-1.0, 1.0)
+}
+
+//celestial bodies other than the reference from the nearest one, None after
+//the farthest
+fn next_target(
+    world: &SubWorld,
+    n_body: &NewtonBody,
+    reference: Entity,
+    current: Option<Entity>,
+) -> Option<Entity> {
+    let mut bodies: Vec<_> = <(Entity, &NewtonBody, &CelestialBody)>::query()
+        .iter(world)
+        .filter(|(id, _, _)| **id != reference)
+        .map(|(id, body, _)| (*id, body.pos.distance(n_body.pos)))
+        .collect();
+    bodies.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let next = match current.and_then(|c| bodies.iter().position(|(id, _)| *id == c)) {
+        Some(i) => i + 1,
+        None => 0,
+    };
+    bodies.get(next).map(|(id, _)| *id)
+}
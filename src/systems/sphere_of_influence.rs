@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use glam::DVec2;
+use legion::{world::SubWorld, *};
+
+use crate::{
+    components::{
+        celestial_body::CelestialBody, newton_body::NewtonBody, reference_body::ReferenceBody,
+        sphere_of_influence::SphereOfInfluence,
+    },
+    events::Events,
+    orbit::soi_radius,
+    physics_clock::PhysicsClock,
+    Dt,
+};
+
+//the generated systems are tight, a Laplace sphere can be smaller than the body
+//itself so it is kept at least this many body radii
+const MIN_SOI_BODY_RADII: f64 = 4.0;
+
+//set when celestial bodies are created or merged, the hierarchy is rebuilt in
+//the next step and kept until then
+pub struct SoiHierarchyStale(pub bool);
+
+//sent when an entity with `ReferenceBody` crosses a sphere of influence boundary
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoiChange {
+    pub entity: Entity,
+    pub from: Entity,
+    pub to: Entity,
+    pub time: f64,
+}
+
+//heaviest body is the root, every other body belongs to the smallest sphere
+//of a heavier body it is inside of (star -> planets -> moons), radii are taken
+//at the distances of the bodies when it is rebuilt
+#[system]
+#[read_component(Entity)]
+#[read_component(NewtonBody)]
+#[read_component(CelestialBody)]
+#[write_component(SphereOfInfluence)]
+pub fn soi_hierarchy(world: &mut SubWorld, #[resource] stale: &mut SoiHierarchyStale) {
+    if !stale.0 {
+        return;
+    }
+    stale.0 = false;
+    let mut query = <(Entity, &NewtonBody, &CelestialBody, &SphereOfInfluence)>::query();
+    let mut bodies: Vec<_> = query
+        .iter(world)
+        .map(|(id, n_body, c_body, _)| (*id, n_body.pos, n_body.mass, c_body.radius))
+        .collect();
+    bodies.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let mut spheres: Vec<(Entity, DVec2, f64, SphereOfInfluence)> =
+        Vec::with_capacity(bodies.len());
+    for (id, pos, mass, radius) in bodies {
+        let parent = spheres
+            .iter()
+            .filter(|(_, p_pos, _, soi)| p_pos.distance(pos) < soi.radius)
+            .min_by(|a, b| a.3.radius.total_cmp(&b.3.radius));
+        let soi = match parent {
+            Some((p_id, p_pos, p_mass, _)) => {
+                let laplace = soi_radius(p_pos.distance(pos), mass, *p_mass);
+                let min = radius * MIN_SOI_BODY_RADII;
+                if laplace < min {
+                    log::info!(
+                        "sphere of influence of {:?} widened from {:.0} m to {:.0} m",
+                        id,
+                        laplace,
+                        min
+                    );
+                }
+                SphereOfInfluence {
+                    parent: Some(*p_id),
+                    radius: laplace.max(min),
+                }
+            }
+            None => SphereOfInfluence::ROOT,
+        };
+        spheres.push((id, pos, mass, soi));
+    }
+
+    let spheres: HashMap<_, _> = spheres
+        .into_iter()
+        .map(|(id, _, _, soi)| (id, soi))
+        .collect();
+    let mut query = <(Entity, &mut SphereOfInfluence)>::query();
+    for (id, soi) in query.iter_mut(world) {
+        *soi = spheres[id];
+    }
+}
+
+//the reference body is the innermost sphere of influence containing the entity
+#[system(for_each)]
+#[read_component(Entity)]
+#[read_component(NewtonBody)]
+#[read_component(CelestialBody)]
+#[read_component(SphereOfInfluence)]
+pub fn reference_body(
+    world: &SubWorld,
+    entity: &Entity,
+    n_body: &NewtonBody,
+    reference: &mut ReferenceBody,
+    #[resource] events: &mut Events<SoiChange>,
+    #[resource] clock: &PhysicsClock,
+    #[resource] dt: &Dt,
+) {
+    let mut query = <(Entity, &NewtonBody, &CelestialBody, &SphereOfInfluence)>::query();
+    let innermost = query
+        .iter(world)
+        .filter(|(_, p_n_body, _, soi)| p_n_body.pos.distance(n_body.pos) < soi.radius)
+        .min_by(|a, b| a.3.radius.total_cmp(&b.3.radius));
+    let (id, p_n_body, p_c_body, _) = match innermost {
+        Some(body) => body,
+        None => return,
+    };
+    if *id != reference.id {
+        events.send(SoiChange {
+            entity: *entity,
+            from: reference.id,
+            to: *id,
+            time: clock.time + dt.0,
+        });
+        reference.id = *id;
+    }
+    let direction = (n_body.pos - p_n_body.pos).normalize_or_zero();
+    reference.closest_surface_point =
+        p_n_body.pos + direction * p_c_body.surface_radius(p_n_body.to_local(direction));
+    reference.closest_surface_point_a = direction;
+}
+
+#[system]
+pub fn log_soi_changes(#[resource] events: &mut Events<SoiChange>) {
+    for change in events.drain() {
+        log::info!(
+            "{:?} left the sphere of influence of {:?} for {:?} at {:.0} s",
+            change.entity,
+            change.from,
+            change.to,
+            change.time
+        );
+    }
+}
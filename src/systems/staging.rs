@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+
+use glam::DVec2;
+use legion::{systems::CommandBuffer, world::SubWorld, *};
+
+use crate::{
+    collision::is_point_behind_segment,
+    components::{
+        celestial_body::CelestialBody,
+        debris::Debris,
+        landing::{Crashed, LandingRelation},
+        newton_body::NewtonBody,
+        reference_body::ReferenceBody,
+        rocket::{PlayerInput, Rocket},
+        spent_stage::{Separating, SpentStage},
+        test_particle::TestParticle,
+    },
+};
+
+//pushes the spent stage and the rocket apart, m/s
+const SEPARATION_SPEED: f64 = 1.0;
+
+//decouples the burning stage and leaves it right behind the rocket, both are
+//pushed apart along the thrust axis keeping their momentum
+#[system(for_each)]
+#[filter(!component::<Crashed>())]
+pub fn staging(
+    command_buffer: &mut CommandBuffer,
+    rocket: &mut Rocket,
+    n_body: &mut NewtonBody,
+    reference: &ReferenceBody,
+    #[resource] input: &mut HashSet<PlayerInput>,
+) {
+    if !input.remove(&PlayerInput::Stage) {
+        return;
+    }
+    if let Some(stage) = rocket.decouple() {
+        let total = n_body.mass;
+        n_body.mass = rocket.mass();
+        n_body.inertia = rocket.inertia();
+        let nose = n_body.angle.rotate(DVec2::NEG_Y);
+        let pos = n_body.pos - nose * 0.5 * (rocket.length + stage.length);
+        let vel = n_body.vel - nose * SEPARATION_SPEED * n_body.mass / total;
+        n_body.vel += nose * SEPARATION_SPEED * stage.mass() / total;
+        command_buffer.push((
+            SpentStage {
+                stage,
+                hull: stage.hull(),
+            },
+            NewtonBody {
+                mass: stage.mass(),
+                inertia: stage.inertia(),
+                pos,
+                prev_pos: pos,
+                vel,
+                force: DVec2::ZERO,
+                torque: 0.0,
+                ..*n_body
+            },
+            TestParticle,
+            *reference,
+            Separating,
+        ));
+    }
+}
+
+//spent stages and debris are destroyed on the surface of their reference body,
+//a rocket wrecked in flight comes to rest there
+#[system(for_each)]
+#[filter(
+    component::<SpentStage>()
+        | component::<Debris>()
+        | (component::<Crashed>() & !component::<LandingRelation>())
+)]
+#[read_component(NewtonBody)]
+#[read_component(CelestialBody)]
+pub fn debris_crash(
+    world: &SubWorld,
+    command_buffer: &mut CommandBuffer,
+    entity: &Entity,
+    n_body: &NewtonBody,
+    reference: &ReferenceBody,
+    rocket: Option<&Rocket>,
+) {
+    let reference_body = match world.entry_ref(reference.id) {
+        Ok(reference_body) => reference_body,
+        Err(_) => return,
+    };
+    let (planet, c_body) = match (
+        reference_body.get_component::<NewtonBody>(),
+        reference_body.get_component::<CelestialBody>(),
+    ) {
+        (Ok(planet), Ok(c_body)) => (planet, c_body),
+        _ => return,
+    };
+    let local = planet.to_local(n_body.pos - planet.pos);
+    let (seg_p1, seg_p2) = c_body.surface_segment(local.normalize_or_zero());
+    if !is_point_behind_segment(local, seg_p1, seg_p2) {
+        return;
+    }
+    log::debug!("{:?} crashed into {:?}", entity, reference.id);
+    if rocket.is_some() {
+        command_buffer.add_component(
+            *entity,
+            LandingRelation {
+                planet_id: reference.id,
+                angle_position: local.normalize_or_zero(),
+                attitude: planet.to_local(n_body.angle),
+            },
+        );
+    } else {
+        command_buffer.remove(*entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use super::*;
+    use crate::{
+        assembly::load_rocket, collision::triangles_penetration, color::Color,
+        components::celestial_body::CelestialBodyType,
+    };
+
+    const RADIUS: f64 = 1e6;
+
+    fn body(pos: DVec2) -> NewtonBody {
+        NewtonBody {
+            angle: DVec2::X,
+            angular_vel: 0.0,
+            inertia: 1.0,
+            mass: 1.0,
+            pos,
+            vel: DVec2::ZERO,
+            acc: DVec2::ZERO,
+            force: DVec2::ZERO,
+            torque: 0.0,
+            prev_pos: pos,
+            prev_angle: DVec2::X,
+        }
+    }
+
+    #[test]
+    fn wrecks_stop_at_the_ground() {
+        let mut world = World::default();
+        let planet = world.push((
+            CelestialBody {
+                b_type: CelestialBodyType::Planet,
+                radius: RADIUS,
+                color: Color::GREEN,
+                atmosphere: None,
+                terrain: None,
+            },
+            body(DVec2::ZERO),
+        ));
+        let reference = ReferenceBody {
+            id: planet,
+            closest_surface_point: DVec2::ZERO,
+            closest_surface_point_a: DVec2::ZERO,
+        };
+        let below = dvec2(0.0, RADIUS - 1.0);
+        let rocket = load_rocket("hopper").unwrap();
+        let hull = rocket.hull();
+        let wreck = world.push((rocket, body(below), reference, Crashed));
+        let debris = world.push((Debris { hull }, body(below), reference));
+        let stage = world.push((
+            SpentStage {
+                stage: *load_rocket("hopper").unwrap().stage(),
+                hull,
+            },
+            body(dvec2(0.0, RADIUS + 1.0)),
+            reference,
+        ));
+
+        let mut resources = Resources::default();
+        let mut schedule = Schedule::builder()
+            .add_system(debris_crash_system())
+            .build();
+        schedule.execute(&mut world, &mut resources);
+
+        let relation = *world
+            .entry(wreck)
+            .unwrap()
+            .get_component::<LandingRelation>()
+            .unwrap();
+        assert_eq!(relation.planet_id, planet);
+        assert!(relation.angle_position.distance(DVec2::Y) < 1e-12);
+        assert!(world.entry(debris).is_none());
+        assert!(world.entry(stage).is_some());
+    }
+
+    #[test]
+    fn spent_stage_separates_behind_the_rocket() {
+        let mut world = World::default();
+        let rocket = load_rocket("saturn_v").unwrap();
+        let length = rocket.length;
+        let mut n_body = body(DVec2::ZERO);
+        n_body.mass = rocket.mass();
+        n_body.vel = dvec2(100.0, 0.0);
+        let momentum = n_body.vel * n_body.mass;
+        let reference = ReferenceBody {
+            id: world.push((body(DVec2::ZERO),)),
+            closest_surface_point: DVec2::ZERO,
+            closest_surface_point_a: DVec2::ZERO,
+        };
+        let entity = world.push((rocket, n_body, reference));
+
+        let mut resources = Resources::default();
+        resources.insert(HashSet::from([PlayerInput::Stage]));
+        let mut schedule = Schedule::builder().add_system(staging_system()).build();
+        schedule.execute(&mut world, &mut resources);
+
+        let entry = world.entry(entity).unwrap();
+        let rocket = entry.get_component::<Rocket>().unwrap().clone();
+        let n_body = *entry.get_component::<NewtonBody>().unwrap();
+        let (stage, stage_body) = <(&SpentStage, &NewtonBody)>::query()
+            .iter(&world)
+            .map(|(stage, body)| (*stage, *body))
+            .next()
+            .unwrap();
+        assert!(rocket.length < length);
+        assert_eq!(rocket.length + stage.stage.length, length);
+
+        let nose = n_body.angle.rotate(DVec2::NEG_Y);
+        let outline =
+            |hull: [DVec2; 3], body: &NewtonBody| hull.map(|p| body.pos + body.angle.rotate(p));
+        //only the nose of the stage touches the base of the rocket
+        let penetration = triangles_penetration(
+            &outline(rocket.hull(), &n_body),
+            &outline(stage.hull, &stage_body),
+        );
+        assert!(penetration.is_none_or(|(_, depth)| depth < 1e-9));
+        assert!((stage_body.pos - n_body.pos).dot(nose) < 0.0);
+        assert!(((n_body.vel - stage_body.vel).dot(nose) - SEPARATION_SPEED).abs() < 1e-9);
+        let after = n_body.vel * n_body.mass + stage_body.vel * stage_body.mass;
+        assert!(after.distance(momentum) < 1e-6 * momentum.length());
+    }
+}
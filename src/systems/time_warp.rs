@@ -0,0 +1,117 @@
+use legion::{world::SubWorld, *};
+
+use crate::components::{
+    celestial_body::CelestialBody, landing::LandingRelation, newton_body::NewtonBody,
+    reference_body::ReferenceBody, rocket::Rocket, sphere_of_influence::SphereOfInfluence,
+};
+
+pub const WARP_LEVELS: [f64; 8] = [1.0, 5.0, 10.0, 50.0, 100.0, 1000.0, 10000.0, 100000.0];
+
+//(altitude / body radius, highest allowed warp level) checked in order
+const ALTITUDE_LIMITS: [(f64, usize); 3] = [(0.05, 0), (0.5, 2), (2.0, 4)];
+//relative distance to a SOI boundary inside which warp is limited
+const SOI_MARGIN: f64 = 0.1;
+const SOI_WARP_LIMIT: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WarpLimit {
+    None,
+    Thrust,
+    Altitude,
+    SoiBoundary,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeWarp {
+    pub level: usize,
+    pub max_level: usize,
+    pub limit: WarpLimit,
+}
+
+impl TimeWarp {
+    pub fn new() -> Self {
+        TimeWarp {
+            level: 0,
+            max_level: WARP_LEVELS.len() - 1,
+            limit: WarpLimit::None,
+        }
+    }
+
+    pub fn factor(&self) -> f64 {
+        WARP_LEVELS[self.level]
+    }
+
+    pub fn max_factor(&self) -> f64 {
+        WARP_LEVELS[self.max_level]
+    }
+
+    pub fn increase(&mut self) {
+        self.level = (self.level + 1).min(self.max_level);
+    }
+
+    pub fn decrease(&mut self) {
+        self.level = self.level.saturating_sub(1);
+    }
+
+    pub fn reset(&mut self) {
+        self.level = 0;
+    }
+
+    fn restrict(&mut self, max_level: usize, limit: WarpLimit) {
+        if max_level < self.max_level {
+            self.max_level = max_level;
+            self.limit = limit;
+        }
+    }
+}
+
+impl Default for TimeWarp {
+    fn default() -> Self {
+        TimeWarp::new()
+    }
+}
+
+#[system(for_each)]
+#[read_component(NewtonBody)]
+#[read_component(CelestialBody)]
+#[read_component(SphereOfInfluence)]
+pub fn time_warp_limit(
+    world: &SubWorld,
+    rocket: &Rocket,
+    n_body: &NewtonBody,
+    reference: &ReferenceBody,
+    landing: Option<&LandingRelation>,
+    #[resource] warp: &mut TimeWarp,
+) {
+    warp.max_level = WARP_LEVELS.len() - 1;
+    warp.limit = WarpLimit::None;
+
+    if rocket.is_thrusting() {
+        warp.restrict(0, WarpLimit::Thrust);
+    }
+
+    if landing.is_none() {
+        let reference_body = world.entry_ref(reference.id).unwrap();
+        let c_body = reference_body.get_component::<CelestialBody>().unwrap();
+        let c_n_body = reference_body.get_component::<NewtonBody>().unwrap();
+        let altitude = n_body.pos.distance(c_n_body.pos) - c_body.radius;
+        if let Some((_, level)) = ALTITUDE_LIMITS
+            .iter()
+            .find(|(ratio, _)| altitude < ratio * c_body.radius)
+        {
+            warp.restrict(*level, WarpLimit::Altitude);
+        }
+    }
+
+    let near_boundary = <(&NewtonBody, &SphereOfInfluence)>::query()
+        .iter(world)
+        .filter(|(_, soi)| soi.parent.is_some())
+        .any(|(body, soi)| {
+            (n_body.pos.distance(body.pos) - soi.radius).abs() < SOI_MARGIN * soi.radius
+        });
+    if near_boundary {
+        warp.restrict(SOI_WARP_LIMIT, WarpLimit::SoiBoundary);
+    }
+
+    warp.level = warp.level.min(warp.max_level);
+}
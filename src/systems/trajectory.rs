@@ -0,0 +1,151 @@
+use std::f64::consts::TAU;
+
+use legion::{world::SubWorld, *};
+
+use crate::{
+    components::{
+        celestial_body::CelestialBody,
+        landing::LandingRelation,
+        newton_body::NewtonBody,
+        reference_body::ReferenceBody,
+        sphere_of_influence::SphereOfInfluence,
+        trajectory::{Trajectory, TrajectoryMarker},
+    },
+    gravity::G,
+    orbit::{Conic, OrbitalElements},
+    physics_clock::PhysicsClock,
+};
+
+const TRAJECTORY_SEGMENTS: usize = 256;
+//open orbits are cut before the asymptote where the radius goes to infinity
+const ASYMPTOTE_MARGIN: f64 = 0.999;
+
+//patched conic of the rocket around its reference body, cut at the surface
+//or at the boundary of the sphere of influence
+#[system(for_each)]
+#[read_component(NewtonBody)]
+#[read_component(CelestialBody)]
+#[read_component(SphereOfInfluence)]
+pub fn trajectory(
+    world: &SubWorld,
+    n_body: &NewtonBody,
+    reference: &ReferenceBody,
+    landing: Option<&LandingRelation>,
+    trajectory: &mut Trajectory,
+    #[resource] clock: &PhysicsClock,
+) {
+    trajectory.clear();
+    if landing.is_some() {
+        return;
+    }
+    if let Some(r) = reference_orbit(world, n_body, reference.id, clock.time) {
+        trajectory.reference = Some(reference.id);
+        predict(&r.orbit, clock.time, r.radius, r.soi, trajectory);
+    }
+}
+
+//orbit of a body around `reference` together with the bounds of the prediction
+pub struct ReferenceOrbit {
+    pub orbit: OrbitalElements,
+    pub radius: f64,
+    pub soi: f64,
+}
+
+//None for a body passing through the center of the reference body
+pub fn reference_orbit(
+    world: &SubWorld,
+    n_body: &NewtonBody,
+    reference: Entity,
+    time: f64,
+) -> Option<ReferenceOrbit> {
+    let reference_body = world.entry_ref(reference).ok()?;
+    let planet = reference_body.get_component::<NewtonBody>().ok()?;
+    let radius = reference_body.get_component::<CelestialBody>().ok()?.radius;
+    let soi = reference_body
+        .get_component::<SphereOfInfluence>()
+        .map(|soi| soi.radius)
+        .unwrap_or(f64::INFINITY);
+    let orbit = OrbitalElements::from_state_vectors(
+        n_body.pos - planet.pos,
+        n_body.vel - planet.vel,
+        G * planet.mass,
+        time,
+    );
+    if orbit.semi_latus_rectum <= 0.0 {
+        return None;
+    }
+    Some(ReferenceOrbit { orbit, radius, soi })
+}
+
+//samples `orbit` from `time` on, marker times are relative to `time`
+pub fn predict(
+    orbit: &OrbitalElements,
+    time: f64,
+    radius: f64,
+    soi: f64,
+    trajectory: &mut Trajectory,
+) {
+    let start = orbit.true_anomaly_at(time);
+    let mut end = match orbit.conic() {
+        Conic::Elliptic => start + TAU,
+        _ => orbit.max_true_anomaly() * ASYMPTOTE_MARGIN,
+    };
+    let marker = |true_anomaly: f64| TrajectoryMarker {
+        pos: orbit.state_vectors_at_true_anomaly(true_anomaly).0,
+        time: orbit
+            .time_to_true_anomaly(time, true_anomaly)
+            .unwrap_or(0.0),
+    };
+
+    //descending through the surface, ascending through the SOI boundary
+    if let Some(crossing) = radius_crossing(orbit, radius) {
+        let impact = next_after(start, -crossing);
+        if impact <= end {
+            end = impact;
+            trajectory.impact = Some(marker(impact));
+        }
+    }
+    if let Some(crossing) = radius_crossing(orbit, soi) {
+        let exit = next_after(start, crossing);
+        if exit <= end {
+            end = exit;
+            trajectory.soi_exit = Some(marker(exit));
+        }
+    }
+
+    let periapsis = next_after(start, 0.0);
+    if periapsis <= end {
+        trajectory.periapsis = Some(marker(periapsis));
+    }
+    if orbit.conic() == Conic::Elliptic {
+        let apoapsis = next_after(start, std::f64::consts::PI);
+        if apoapsis <= end {
+            trajectory.apoapsis = Some(marker(apoapsis));
+        }
+    }
+
+    trajectory.points = (0..=TRAJECTORY_SEGMENTS)
+        .map(|i| {
+            let true_anomaly = start + (end - start) * i as f64 / TRAJECTORY_SEGMENTS as f64;
+            orbit.state_vectors_at_true_anomaly(true_anomaly).0
+        })
+        .collect();
+}
+
+//positive true anomaly at which the orbit reaches `r`, None if it never does
+fn radius_crossing(orbit: &OrbitalElements, r: f64) -> Option<f64> {
+    if !r.is_finite() || orbit.eccentricity == 0.0 {
+        return None;
+    }
+    let cos = (orbit.semi_latus_rectum / r - 1.0) / orbit.eccentricity;
+    if (-1.0..=1.0).contains(&cos) {
+        Some(cos.acos())
+    } else {
+        None
+    }
+}
+
+//`target` shifted by whole turns to the first angle not before `from`
+fn next_after(from: f64, target: f64) -> f64 {
+    target + TAU * ((from - target) / TAU).ceil()
+}
@@ -0,0 +1,75 @@
+//runs without the render feature: cargo test --no-default-features
+use ksp2d::{
+    assembly::load_rocket,
+    components::{newton_body::NewtonBody, sphere_of_influence::SphereOfInfluence},
+    settings::Settings,
+    simulation::Simulation,
+    systems::conservation::ConservationInfo,
+};
+use legion::*;
+
+const STEPS: usize = 200;
+
+//the bundled rocket in a fixed star system
+fn simulation() -> Simulation {
+    let args = ["--seed", "42"].iter().map(|a| a.to_string());
+    let settings = Settings::from_args(args).unwrap();
+    let rocket = load_rocket(&settings.rocket).unwrap();
+    Simulation::new(&settings, rocket)
+}
+
+#[test]
+fn headless_simulation_stays_finite() {
+    let mut simulation = simulation();
+
+    let mut time = simulation.time();
+    for step in 0..STEPS {
+        simulation.step();
+        //a headless run drains its own events
+        simulation.drain_soi_changes();
+        simulation.drain_touchdowns();
+        assert!(simulation.time() > time, "time stalled at step {}", step);
+        time = simulation.time();
+
+        let mut query = <&NewtonBody>::query();
+        for n_body in query.iter(&simulation.world) {
+            assert!(
+                n_body.pos.is_finite(),
+                "non-finite position at step {}",
+                step
+            );
+        }
+    }
+}
+
+#[test]
+fn first_step_builds_the_soi_hierarchy() {
+    let mut simulation = simulation();
+    simulation.step();
+
+    let roots = <&SphereOfInfluence>::query()
+        .iter(&simulation.world)
+        .filter(|soi| soi.parent.is_none())
+        .count();
+    assert_eq!(roots, 1);
+}
+
+#[test]
+fn drift_is_tracked_from_the_start_without_a_panel() {
+    let mut simulation = simulation();
+    simulation.step();
+    let initial = simulation
+        .resources
+        .get::<ConservationInfo>()
+        .unwrap()
+        .initial;
+    assert!(initial.is_some());
+
+    for _ in 0..STEPS {
+        simulation.step();
+    }
+    let info = *simulation.resources.get::<ConservationInfo>().unwrap();
+    assert_eq!(info.initial, initial);
+    assert!(info.current != initial);
+    assert!(info.energy_drift.is_finite());
+}